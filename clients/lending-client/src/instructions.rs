@@ -0,0 +1,505 @@
+use crate::pda::{
+    find_bank_address, find_collateral_vault_address, find_global_config_address,
+    find_program_data_address, find_receipt_mint_address, find_treasury_address, find_user_address,
+    find_virtual_deposit_vault_address, get_associated_token_address,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData};
+use lending::decimal::Decimal;
+use lending::states::{BankConfig, OracleConfig};
+use lending::{accounts, instruction};
+
+fn build_instruction(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let mut account_metas = accounts.to_account_metas(None);
+    for bank in remaining_accounts {
+        account_metas.push(AccountMeta::new_readonly(*bank, false));
+    }
+
+    Instruction {
+        program_id: lending::ID,
+        accounts: account_metas,
+        data: data.data(),
+    }
+}
+
+pub fn init_global_config(signer: Pubkey, admin: Pubkey, fee_recipient: Pubkey) -> Instruction {
+    let accounts = accounts::InitGlobalConfig {
+        signer,
+        global_config: find_global_config_address().0,
+        program: lending::ID,
+        program_data: find_program_data_address().0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::ProcessInitGlobalConfig {
+        admin,
+        fee_recipient,
+    };
+
+    build_instruction(accounts, data, &[])
+}
+
+pub fn init_bank(
+    admin: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    oracle_config: OracleConfig,
+    bank_config: BankConfig,
+) -> Instruction {
+    let accounts = accounts::InitBank {
+        signer: admin,
+        global_config: find_global_config_address().0,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        bank_token_account: find_treasury_address(&mint).0,
+        receipt_mint: find_receipt_mint_address(&mint).0,
+        collateral_vault: find_collateral_vault_address(&mint).0,
+        virtual_deposit_vault: find_virtual_deposit_vault_address(&mint).0,
+        system_program: system_program::ID,
+        token_program,
+    };
+    let data = instruction::ProcessInitBank {
+        oracle_config,
+        bank_config,
+    };
+
+    build_instruction(accounts, data, &[])
+}
+
+pub fn init_user(owner: Pubkey) -> Instruction {
+    let accounts = accounts::InitUser {
+        signer: owner,
+        user_account: find_user_address(&owner).0,
+        system_program: system_program::ID,
+    };
+
+    build_instruction(accounts, instruction::ProcessInitUser {}, &[])
+}
+
+///remaining_accounts: 有借款时, 用户其它每个仓位的 bank_account, 按仓位顺序, 使用 Bank 缓存的价格
+pub fn deposit(
+    owner: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    price_update: Pubkey,
+    amount: u64,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let accounts = accounts::Deposit {
+        signer: owner,
+        global_config: find_global_config_address().0,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        bank_token_account: find_treasury_address(&mint).0,
+        receipt_mint: find_receipt_mint_address(&mint).0,
+        collateral_vault: find_collateral_vault_address(&mint).0,
+        user_account: find_user_address(&owner).0,
+        user_token_account: get_associated_token_address(&owner, &mint, &token_program),
+        price_update,
+        system_program: system_program::ID,
+        token_program,
+        associated_token_program: anchor_spl::associated_token::ID,
+    };
+
+    build_instruction(
+        accounts,
+        instruction::ProcessDeposit { amount },
+        remaining_accounts,
+    )
+}
+
+///remaining_accounts: 用户其它每个仓位的 bank_account, 按仓位顺序, 使用 Bank 缓存的价格
+pub fn borrow(
+    owner: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    price_update: Pubkey,
+    to_borrow_value: Decimal,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let accounts = accounts::Borrow {
+        signer: owner,
+        global_config: find_global_config_address().0,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        bank_token_account: find_treasury_address(&mint).0,
+        user_account: find_user_address(&owner).0,
+        user_token_account: get_associated_token_address(&owner, &mint, &token_program),
+        price_update,
+        system_program: system_program::ID,
+        token_program,
+        associated_token_program: anchor_spl::associated_token::ID,
+    };
+    let data = instruction::ProcessBorrow { to_borrow_value };
+
+    build_instruction(accounts, data, remaining_accounts)
+}
+
+///remaining_accounts: 部分还款后仍有借款时, 用户其它每个仓位的 bank_account, 按仓位顺序, 使用 Bank 缓存的价格
+pub fn repay(
+    owner: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    price_update: Pubkey,
+    to_repay_amount: u64,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let accounts = accounts::Repay {
+        signer: owner,
+        global_config: find_global_config_address().0,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        bank_token_account: find_treasury_address(&mint).0,
+        user_account: find_user_address(&owner).0,
+        user_token_account: get_associated_token_address(&owner, &mint, &token_program),
+        price_update,
+        system_program: system_program::ID,
+        token_program,
+        associated_token_program: anchor_spl::associated_token::ID,
+    };
+    let data = instruction::ProcessRepay { to_repay_amount };
+
+    build_instruction(accounts, data, remaining_accounts)
+}
+
+///remaining_accounts: 用户其它每个仓位的 bank_account, 按仓位顺序, 使用 Bank 缓存的价格
+pub fn withdraw(
+    owner: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    price_update: Pubkey,
+    amount: u64,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let accounts = accounts::WithDraw {
+        signer: owner,
+        global_config: find_global_config_address().0,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        bank_token_account: find_treasury_address(&mint).0,
+        receipt_mint: find_receipt_mint_address(&mint).0,
+        collateral_vault: find_collateral_vault_address(&mint).0,
+        user_account: find_user_address(&owner).0,
+        user_token_account: get_associated_token_address(&owner, &mint, &token_program),
+        price_update,
+        system_program: system_program::ID,
+        token_program,
+        associated_token_program: anchor_spl::associated_token::ID,
+    };
+
+    build_instruction(
+        accounts,
+        instruction::ProcessWithdraw { amount },
+        remaining_accounts,
+    )
+}
+
+///remaining_accounts: 被清算用户其它每个仓位的 bank_account, 按仓位顺序, 使用 Bank 缓存的价格
+#[allow(clippy::too_many_arguments)]
+pub fn liquidate(
+    liquidator: Pubkey,
+    owner: Pubkey,
+    collateral_mint: Pubkey,
+    borrowed_mint: Pubkey,
+    token_program: Pubkey,
+    collateral_price_update: Pubkey,
+    borrowed_price_update: Pubkey,
+    to_repay_amount: u64,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let accounts = accounts::Liquidate {
+        signer: liquidator,
+        global_config: find_global_config_address().0,
+        collateral_mint,
+        borrowed_mint,
+        collateral_bank_account: find_bank_address(&collateral_mint).0,
+        collateral_bank_token_account: find_treasury_address(&collateral_mint).0,
+        collateral_receipt_mint: find_receipt_mint_address(&collateral_mint).0,
+        collateral_vault: find_collateral_vault_address(&collateral_mint).0,
+        borrowed_bank_account: find_bank_address(&borrowed_mint).0,
+        borrowed_bank_token_account: find_treasury_address(&borrowed_mint).0,
+        user_account: find_user_address(&owner).0,
+        liquidator_collateral_token_account: get_associated_token_address(
+            &liquidator,
+            &collateral_mint,
+            &token_program,
+        ),
+        liquidator_borrowed_token_account: get_associated_token_address(
+            &liquidator,
+            &borrowed_mint,
+            &token_program,
+        ),
+        collateral_price_update,
+        borrowed_price_update,
+        system_program: system_program::ID,
+        token_program,
+        associated_token_program: anchor_spl::associated_token::ID,
+    };
+    let data = instruction::ProcessLiquidate { to_repay_amount };
+
+    build_instruction(accounts, data, remaining_accounts)
+}
+
+///remaining_accounts: 有借款时, 用户其它每个仓位的 bank_account, 按仓位顺序, 使用 Bank 缓存的价格
+pub fn lock_collateral(
+    owner: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    price_update: Pubkey,
+    shares: u64,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let receipt_mint = find_receipt_mint_address(&mint).0;
+    let accounts = accounts::LockCollateral {
+        signer: owner,
+        global_config: find_global_config_address().0,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        receipt_mint,
+        collateral_vault: find_collateral_vault_address(&mint).0,
+        user_account: find_user_address(&owner).0,
+        user_receipt_token_account: get_associated_token_address(
+            &owner,
+            &receipt_mint,
+            &token_program,
+        ),
+        price_update,
+        token_program,
+        associated_token_program: anchor_spl::associated_token::ID,
+    };
+
+    build_instruction(
+        accounts,
+        instruction::ProcessLockCollateral { shares },
+        remaining_accounts,
+    )
+}
+
+///remaining_accounts: 用户其它每个仓位的 bank_account, 按仓位顺序, 使用 Bank 缓存的价格
+pub fn unlock_collateral(
+    owner: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    price_update: Pubkey,
+    shares: u64,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let receipt_mint = find_receipt_mint_address(&mint).0;
+    let accounts = accounts::UnlockCollateral {
+        signer: owner,
+        global_config: find_global_config_address().0,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        receipt_mint,
+        collateral_vault: find_collateral_vault_address(&mint).0,
+        user_account: find_user_address(&owner).0,
+        user_receipt_token_account: get_associated_token_address(
+            &owner,
+            &receipt_mint,
+            &token_program,
+        ),
+        price_update,
+        system_program: system_program::ID,
+        token_program,
+        associated_token_program: anchor_spl::associated_token::ID,
+    };
+
+    build_instruction(
+        accounts,
+        instruction::ProcessUnlockCollateral { shares },
+        remaining_accounts,
+    )
+}
+
+///同一交易中必须在其后包含 flash_repay
+pub fn flash_borrow(
+    borrower: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    user_token_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = accounts::FlashBorrow {
+        signer: borrower,
+        global_config: find_global_config_address().0,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        bank_token_account: find_treasury_address(&mint).0,
+        user_token_account,
+        instructions: sysvar::instructions::ID,
+        token_program,
+    };
+
+    build_instruction(accounts, instruction::ProcessFlashBorrow { amount }, &[])
+}
+
+pub fn flash_repay(
+    borrower: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    user_token_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::FlashRepay {
+        signer: borrower,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        bank_token_account: find_treasury_address(&mint).0,
+        user_token_account,
+        token_program,
+    };
+
+    build_instruction(accounts, instruction::ProcessFlashRepay {}, &[])
+}
+
+pub fn update_bank_oracle(
+    authority: Pubkey,
+    mint: Pubkey,
+    oracle_config: OracleConfig,
+) -> Instruction {
+    let accounts = accounts::UpdateBankOracle {
+        authority,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+    };
+    let data = instruction::ProcessUpdateBankOracle { oracle_config };
+
+    build_instruction(accounts, data, &[])
+}
+
+pub fn update_bank_config(authority: Pubkey, mint: Pubkey, bank_config: BankConfig) -> Instruction {
+    let accounts = accounts::UpdateBankConfig {
+        authority,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+    };
+    let data = instruction::ProcessUpdateBankConfig { bank_config };
+
+    build_instruction(accounts, data, &[])
+}
+
+pub fn propose_bank_authority(
+    authority: Pubkey,
+    mint: Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    let accounts = accounts::ProposeBankAuthority {
+        authority,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+    };
+    let data = instruction::ProcessProposeBankAuthority { new_authority };
+
+    build_instruction(accounts, data, &[])
+}
+
+pub fn accept_bank_authority(pending_authority: Pubkey, mint: Pubkey) -> Instruction {
+    let accounts = accounts::AcceptBankAuthority {
+        pending_authority,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+    };
+
+    build_instruction(accounts, instruction::ProcessAcceptBankAuthority {}, &[])
+}
+
+pub fn propose_admin(admin: Pubkey, new_admin: Pubkey) -> Instruction {
+    let accounts = accounts::ProposeAdmin {
+        admin,
+        global_config: find_global_config_address().0,
+    };
+
+    build_instruction(
+        accounts,
+        instruction::ProcessProposeAdmin { new_admin },
+        &[],
+    )
+}
+
+pub fn accept_admin(pending_admin: Pubkey) -> Instruction {
+    let accounts = accounts::AcceptAdmin {
+        pending_admin,
+        global_config: find_global_config_address().0,
+    };
+
+    build_instruction(accounts, instruction::ProcessAcceptAdmin {}, &[])
+}
+
+pub fn set_guardian(admin: Pubkey, guardian: Pubkey) -> Instruction {
+    let accounts = accounts::SetGuardian {
+        admin,
+        global_config: find_global_config_address().0,
+    };
+
+    build_instruction(accounts, instruction::ProcessSetGuardian { guardian }, &[])
+}
+
+pub fn set_fee_recipient(admin: Pubkey, fee_recipient: Pubkey) -> Instruction {
+    let accounts = accounts::SetFeeRecipient {
+        admin,
+        global_config: find_global_config_address().0,
+    };
+    let data = instruction::ProcessSetFeeRecipient { fee_recipient };
+
+    build_instruction(accounts, data, &[])
+}
+
+///mint 为 None 时设置全局暂停标志位
+pub fn set_pause_flags(signer: Pubkey, mint: Option<Pubkey>, pause_flags: u64) -> Instruction {
+    let accounts = accounts::SetPauseFlags {
+        signer,
+        global_config: find_global_config_address().0,
+        bank_account: mint.map(|mint| find_bank_address(&mint).0),
+    };
+
+    build_instruction(
+        accounts,
+        instruction::ProcessSetPauseFlags { pause_flags },
+        &[],
+    )
+}
+
+pub fn withdraw_protocol_fees(
+    admin: Pubkey,
+    fee_recipient: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = accounts::WithdrawProtocolFees {
+        admin,
+        global_config: find_global_config_address().0,
+        fee_recipient,
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        bank_token_account: find_treasury_address(&mint).0,
+        fee_recipient_token_account: get_associated_token_address(
+            &fee_recipient,
+            &mint,
+            &token_program,
+        ),
+        system_program: system_program::ID,
+        token_program,
+        associated_token_program: anchor_spl::associated_token::ID,
+    };
+
+    build_instruction(
+        accounts,
+        instruction::ProcessWithdrawProtocolFees { amount },
+        &[],
+    )
+}
+
+pub fn refresh_bank(mint: Pubkey, price_update: Pubkey) -> Instruction {
+    let accounts = accounts::RefreshBank {
+        mint,
+        bank_account: find_bank_address(&mint).0,
+        price_update,
+    };
+
+    build_instruction(accounts, instruction::ProcessRefreshBank {}, &[])
+}
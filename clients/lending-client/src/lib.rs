@@ -0,0 +1,7 @@
+pub mod instructions;
+pub mod pda;
+pub mod state;
+
+pub use instructions::*;
+pub use pda::*;
+pub use state::*;
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use lending::constants::{
+    COLLATERAL_VAULT, GLOBAL_CONFIG, RECEIPT_MINT, TREASURY, VIRTUAL_DEPOSIT_VAULT,
+};
+
+pub fn find_global_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_CONFIG.as_bytes()], &lending::ID)
+}
+
+pub fn find_bank_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[mint.as_ref()], &lending::ID)
+}
+
+///Bank 的资金池 token 账户
+pub fn find_treasury_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY.as_bytes(), mint.as_ref()], &lending::ID)
+}
+
+pub fn find_receipt_mint_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_MINT.as_bytes(), mint.as_ref()], &lending::ID)
+}
+
+pub fn find_collateral_vault_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COLLATERAL_VAULT.as_bytes(), mint.as_ref()], &lending::ID)
+}
+
+///锁定虚拟存款凭证的 token 账户
+pub fn find_virtual_deposit_vault_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VIRTUAL_DEPOSIT_VAULT.as_bytes(), mint.as_ref()],
+        &lending::ID,
+    )
+}
+
+pub fn find_user_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[owner.as_ref()], &lending::ID)
+}
+
+///程序的 ProgramData 账户, 用于校验升级权限
+pub fn find_program_data_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[lending::ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+pub fn get_associated_token_address(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
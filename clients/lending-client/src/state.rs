@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use lending::constants::SECONDS_PER_YEAR;
+use lending::decimal::Decimal;
+use lending::enums::Rounding;
+use lending::errors::ErrorCode;
+use lending::health_utils::PositionsValue;
+use lending::math_utils::{calc_compound_growth, calc_shares_value};
+use lending::states::{Bank, Position, User};
+
+///带价格的 Bank 快照, 用于离线计算健康因子
+pub struct BankSnapshot {
+    pub key: Pubkey,
+    pub bank: Bank,
+    pub price: i64,
+    pub exponent: i32,
+}
+
+pub fn deserialize_bank(data: &[u8]) -> Result<Bank> {
+    Bank::try_deserialize(&mut &data[..])
+}
+
+pub fn deserialize_user(data: &[u8]) -> Result<User> {
+    User::try_deserialize(&mut &data[..])
+}
+
+///返回结算利息到 current_time 后的 Bank 副本
+pub fn accrued_bank(bank: &Bank, current_time: i64) -> Result<Bank> {
+    let mut bank = bank.clone();
+    bank.update_interest(current_time)?;
+
+    Ok(bank)
+}
+
+///仓位的存款金额, bank 需已结算利息
+pub fn position_deposited_amount(bank: &Bank, position: &Position) -> Result<u64> {
+    calc_shares_value(position.deposited_shares, bank.supply_index, Rounding::Down)
+}
+
+///仓位的借款金额, bank 需已结算利息
+pub fn position_borrowed_amount(bank: &Bank, position: &Position) -> Result<u64> {
+    calc_shares_value(position.borrowed_shares, bank.borrow_index, Rounding::Up)
+}
+
+///年化利率对应的年化收益率, 以 e^r 的三阶泰勒展开近似连续复利, 等于链上一次结算一整年借款利息的增长
+///
+///链上每次结算按间隔复利 (存款指数为单利), 实际收益率随结算频率变化, 结算越频繁越接近此值
+pub fn calc_apy(annual_rate: Decimal) -> Result<Decimal> {
+    let rate = annual_rate.try_div_u64(SECONDS_PER_YEAR, Rounding::Down)?;
+
+    calc_compound_growth(rate, SECONDS_PER_YEAR as i64)?.try_sub(Decimal::ONE)
+}
+
+pub fn borrow_apy(bank: &Bank) -> Result<Decimal> {
+    calc_apy(bank.interest_rate_model.borrow_rate(bank.utilization()?)?)
+}
+
+pub fn supply_apy(bank: &Bank) -> Result<Decimal> {
+    calc_apy(
+        bank.interest_rate_model
+            .supply_rate(bank.utilization()?, bank.reserve_factor)?,
+    )
+}
+
+///汇总用户所有仓位的价值, banks 需包含用户每个仓位的 Bank 且已结算利息
+pub fn calc_user_positions_value(user: &User, banks: &[BankSnapshot]) -> Result<PositionsValue> {
+    let mut positions_value = PositionsValue::default();
+    for position in user.positions.iter() {
+        let snapshot = banks
+            .iter()
+            .find(|snapshot| snapshot.key == position.bank)
+            .ok_or(ErrorCode::InvalidRemainingAccounts)?;
+        positions_value.add_position(
+            position,
+            &snapshot.bank,
+            snapshot.price,
+            snapshot.exponent,
+        )?;
+    }
+
+    Ok(positions_value)
+}
+
+pub fn calc_health_factor(user: &User, banks: &[BankSnapshot]) -> Result<Decimal> {
+    calc_user_positions_value(user, banks)?.health_factor()
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::Pubkey;
+use lending::constants::SECONDS_PER_YEAR;
+use lending::decimal::Decimal;
+use lending::errors::ErrorCode;
+use lending::states::{Bank, InterestRateModel, Position, User};
+use lending_client::{borrow_apy, calc_apy, calc_health_factor, BankSnapshot};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+const PRICE_EXPONENT: i32 = -8;
+///$150
+const SOL_PRICE: i64 = 15_000_000_000;
+///$1
+const USDC_PRICE: i64 = 100_000_000;
+const SOL: u64 = 1_000_000_000;
+const USDC: u64 = 1_000_000;
+
+///年化 10% 的固定借款利率, 利用率变化不影响利率
+fn borrowing_bank() -> Bank {
+    Bank {
+        mint_decimals: 6,
+        supply_index: Decimal::ONE,
+        borrow_index: Decimal::ONE,
+        total_deposited_amount: 1_000 * USDC,
+        total_deposited_shares: 1_000 * USDC,
+        total_borrowed_amount: 500 * USDC,
+        total_borrowed_shares: 500 * USDC,
+        interest_rate_model: InterestRateModel {
+            base_rate: Decimal::from_bps(1_000),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn snapshot(mint_decimals: u8, liquidate_threshold: u64, price: i64) -> BankSnapshot {
+    BankSnapshot {
+        key: Pubkey::new_unique(),
+        bank: Bank {
+            mint_decimals,
+            supply_index: Decimal::ONE,
+            borrow_index: Decimal::ONE,
+            liquidate_threshold: Decimal::from_bps(liquidate_threshold),
+            ..Default::default()
+        },
+        price,
+        exponent: PRICE_EXPONENT,
+    }
+}
+
+fn assert_close(left: Decimal, right: Decimal, tolerance: Decimal) {
+    let diff = if left > right {
+        left.try_sub(right).unwrap()
+    } else {
+        right.try_sub(left).unwrap()
+    };
+    assert!(diff <= tolerance, "{left:?} vs {right:?}");
+}
+
+#[test]
+fn calc_apy_of_zero_rate_is_zero() {
+    assert_eq!(calc_apy(Decimal::ZERO).unwrap(), Decimal::ZERO);
+}
+
+#[test]
+fn borrow_apy_matches_one_year_accrual() {
+    let mut bank = borrowing_bank();
+    let apy = borrow_apy(&bank).unwrap();
+    bank.update_interest(SECONDS_PER_YEAR as i64).unwrap();
+
+    //链上每秒利率向上取整, 一年内的差异远小于 1e-9
+    let growth = bank.borrow_index.try_sub(Decimal::ONE).unwrap();
+    assert_close(apy, growth, Decimal(1_000_000_000));
+}
+
+#[test]
+fn borrow_apy_approximates_daily_accrual() {
+    let mut bank = borrowing_bank();
+    let apy = borrow_apy(&bank).unwrap();
+    for day in 1..=365 {
+        bank.update_interest(day * SECONDS_PER_DAY).unwrap();
+    }
+
+    //按日结算与连续复利相差不到 1bp
+    let growth = bank.borrow_index.try_sub(Decimal::ONE).unwrap();
+    assert_close(apy, growth, Decimal::from_bps(1));
+}
+
+#[test]
+fn health_factor_across_positions() {
+    let sol = snapshot(9, 8_000, SOL_PRICE);
+    let usdc = snapshot(6, 8_500, USDC_PRICE);
+    let mut user = User {
+        positions: vec![Position {
+            bank: sol.key,
+            deposited_shares: 10 * SOL,
+            borrowed_shares: 0,
+        }],
+        ..Default::default()
+    };
+    let banks = [sol, usdc];
+    assert_eq!(calc_health_factor(&user, &banks).unwrap(), Decimal::MAX);
+
+    //与链上借款后的健康因子一致: 10 SOL * $150 * 0.8 / 1000
+    user.positions.push(Position {
+        bank: banks[1].key,
+        deposited_shares: 0,
+        borrowed_shares: 1_000 * USDC,
+    });
+    assert_eq!(
+        calc_health_factor(&user, &banks).unwrap(),
+        Decimal::from_bps(12_000)
+    );
+
+    let result = calc_health_factor(&user, &banks[..1]);
+    assert_eq!(
+        result.unwrap_err(),
+        ErrorCode::InvalidRemainingAccounts.into()
+    );
+}
@@ -0,0 +1,8 @@
+use anchor_lang::prelude::*;
+
+#[constant]
+pub const MAX_POSITIONS: usize = 8;
+
+///init_bank 时锁定的虚拟存款为 10^-VIRTUAL_DEPOSIT_DECIMALS 个代币, 防止首个存款人通过捐赠抬高份额价格
+#[constant]
+pub const VIRTUAL_DEPOSIT_DECIMALS: u8 = 3;
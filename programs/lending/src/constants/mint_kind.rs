@@ -1,5 +0,0 @@
-use anchor_lang::prelude::*;
-
-#[constant]
-pub const SOL: &str = "SOL";
-pub const USDC: &str = "USDC";
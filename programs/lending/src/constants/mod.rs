@@ -1,9 +1,11 @@
 pub mod discriminator;
+pub mod limits;
+pub mod pause_flags;
 pub mod seeds;
-pub mod mint_kind;
-pub mod feed_ids;
+pub mod time;
 
 pub use discriminator::*;
+pub use limits::*;
+pub use pause_flags::*;
 pub use seeds::*;
-pub use mint_kind::*;
-pub use feed_ids::*;
\ No newline at end of file
+pub use time::*;
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+#[constant]
+pub const PAUSE_DEPOSIT: u64 = 1 << 0;
+
+#[constant]
+pub const PAUSE_BORROW: u64 = 1 << 1;
+
+#[constant]
+pub const PAUSE_REPAY: u64 = 1 << 2;
+
+#[constant]
+pub const PAUSE_WITHDRAW: u64 = 1 << 3;
+
+#[constant]
+pub const PAUSE_LIQUIDATE: u64 = 1 << 4;
+
+#[constant]
+pub const PAUSE_FLASH_LOAN: u64 = 1 << 5;
+
+///紧急模式: 只允许取款、还款和清算
+#[constant]
+pub const PAUSE_EMERGENCY: u64 = PAUSE_DEPOSIT | PAUSE_BORROW | PAUSE_FLASH_LOAN;
+
+#[constant]
+pub const PAUSE_ALL: u64 = PAUSE_DEPOSIT
+    | PAUSE_BORROW
+    | PAUSE_REPAY
+    | PAUSE_WITHDRAW
+    | PAUSE_LIQUIDATE
+    | PAUSE_FLASH_LOAN;
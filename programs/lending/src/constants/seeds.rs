@@ -2,3 +2,15 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const TREASURY: &str = "treasury";
+
+#[constant]
+pub const GLOBAL_CONFIG: &str = "global_config";
+
+#[constant]
+pub const RECEIPT_MINT: &str = "receipt_mint";
+
+#[constant]
+pub const COLLATERAL_VAULT: &str = "collateral_vault";
+
+#[constant]
+pub const VIRTUAL_DEPOSIT_VAULT: &str = "virtual_deposit_vault";
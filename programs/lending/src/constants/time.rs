@@ -0,0 +1,4 @@
+use anchor_lang::prelude::*;
+
+#[constant]
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
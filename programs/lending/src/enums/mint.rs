@@ -1,5 +0,0 @@
-#[derive(PartialEq)]
-pub enum MintKind {
-    SOL,
-    USDC
-}
\ No newline at end of file
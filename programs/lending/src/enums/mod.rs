@@ -1,5 +1,5 @@
-pub mod mint;
-pub mod share_op;
+pub mod oracle_type;
+pub mod rounding;
 
-pub use mint::*;
-pub use share_op::*;
\ No newline at end of file
+pub use oracle_type::*;
+pub use rounding::*;
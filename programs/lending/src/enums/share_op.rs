@@ -1,4 +0,0 @@
-pub enum ShareOp {
-    Increase,
-    Decrease,
-}
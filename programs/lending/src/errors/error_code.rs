@@ -4,18 +4,12 @@ pub enum ErrorCode {
     #[msg("build error occurred!")]
     BuilderError,
 
-    #[msg("Get clock time error!")]
-    GetClockTimeError,
-
     #[msg("Deposited Amount less or equal than zero!")]
     DepositedAmountLessOrEqualZero,
 
     #[msg("Deposited Value less or equal than zero!")]
     DepositedValueLessOrEqualZero,
 
-    #[msg("Unsupported mint kind!")]
-    UnsupportedMintKind,
-
     #[msg("Not enough deposited assets!")]
     NotEnoughLiquidationAssets,
 
@@ -25,9 +19,81 @@ pub enum ErrorCode {
     #[msg("repay amount exceeds borrowed amount")]
     RepayExceedBorrowed,
 
-    #[msg("Borrow not allowed!")]
-    BorrowNotAllowed,
-
     #[msg("Not enough balance!")]
     NotEnoughBalance,
+
+    #[msg("User is not liquidatable!")]
+    NotLiquidatable,
+
+    #[msg("Liquidate amount exceeds close factor!")]
+    LiquidateExceedCloseFactor,
+
+    #[msg("Position exceeds max LTV!")]
+    ExceedMaxLtv,
+
+    #[msg("Not enough liquidity in bank!")]
+    InsufficientLiquidity,
+
+    #[msg("Math overflow!")]
+    MathOverflow,
+
+    #[msg("Divide by zero!")]
+    DivideByZero,
+
+    #[msg("Invalid oracle price!")]
+    InvalidPrice,
+
+    #[msg("Too many positions!")]
+    TooManyPositions,
+
+    #[msg("Invalid remaining accounts!")]
+    InvalidRemainingAccounts,
+
+    #[msg("Invalid oracle config!")]
+    InvalidOracleConfig,
+
+    #[msg("Price account does not match bank oracle config!")]
+    InvalidOracleAccount,
+
+    #[msg("Unauthorized!")]
+    Unauthorized,
+
+    #[msg("Invalid bank config!")]
+    InvalidBankConfig,
+
+    #[msg("Operation is paused!")]
+    OperationPaused,
+
+    #[msg("Invalid pause flags!")]
+    InvalidPauseFlags,
+
+    #[msg("Deposit exceeds bank deposit cap!")]
+    DepositCapExceeded,
+
+    #[msg("Borrow exceeds bank borrow cap!")]
+    BorrowCapExceeded,
+
+    #[msg("Withdraw exceeds accumulated protocol fees!")]
+    ExceedProtocolFees,
+
+    #[msg("Flash loan already in progress!")]
+    FlashLoanInProgress,
+
+    #[msg("No flash loan in progress!")]
+    NoFlashLoanInProgress,
+
+    #[msg("Matching flash repay instruction not found!")]
+    FlashRepayNotFound,
+
+    #[msg("Flash loan cannot be called via CPI!")]
+    FlashLoanCpiNotAllowed,
+
+    #[msg("Cached bank price is stale!")]
+    StalePriceCache,
+
+    #[msg("Outflow exceeds bank rate limit!")]
+    OutflowLimitExceeded,
+
+    #[msg("Deposit amount too small to mint any shares!")]
+    DepositTooSmall,
 }
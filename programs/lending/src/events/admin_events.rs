@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct InitGlobalConfigEvent {
+    pub admin: Pubkey,
+    pub fee_recipient: Pubkey,
+}
+
+#[event]
+pub struct ProposeBankAuthorityEvent {
+    pub bank: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AcceptBankAuthorityEvent {
+    pub bank: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ProposeAdminEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AcceptAdminEvent {
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct SetGuardianEvent {
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct SetFeeRecipientEvent {
+    pub fee_recipient: Pubkey,
+}
+
+///bank 为 None 时为全局暂停标志位
+#[event]
+pub struct SetPauseFlagsEvent {
+    pub signer: Pubkey,
+    pub bank: Option<Pubkey>,
+    pub pause_flags: u64,
+}
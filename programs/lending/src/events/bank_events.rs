@@ -0,0 +1,78 @@
+use crate::decimal::Decimal;
+use crate::states::{BankConfig, CachedPrice, OracleConfig};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct InitBankEvent {
+    pub bank: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub oracle_config: OracleConfig,
+    pub bank_config: BankConfig,
+}
+
+#[event]
+pub struct UpdateBankConfigEvent {
+    pub bank: Pubkey,
+    pub mint: Pubkey,
+    pub bank_config: BankConfig,
+}
+
+#[event]
+pub struct UpdateBankOracleEvent {
+    pub bank: Pubkey,
+    pub mint: Pubkey,
+    pub oracle_config: OracleConfig,
+}
+
+///利息结算, Bank 账户由 mint 推导
+#[event]
+pub struct AccrueInterestEvent {
+    pub mint: Pubkey,
+    pub elapsed: i64,
+    pub utilization: Decimal,
+    pub supply_index: Decimal,
+    pub borrow_index: Decimal,
+    pub total_deposited_amount: u64,
+    pub total_borrowed_amount: u64,
+    ///本次计入的协议收入
+    pub protocol_fees: u64,
+}
+
+#[event]
+pub struct WithdrawProtocolFeesEvent {
+    pub bank: Pubkey,
+    pub mint: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub amount: u64,
+    pub accumulated_protocol_fees: u64,
+}
+
+#[event]
+pub struct FlashBorrowEvent {
+    pub bank: Pubkey,
+    pub mint: Pubkey,
+    pub borrower: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FlashRepayEvent {
+    pub bank: Pubkey,
+    pub mint: Pubkey,
+    pub borrower: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub protocol_fee: u64,
+    pub supply_index: Decimal,
+}
+
+#[event]
+pub struct RefreshBankEvent {
+    pub bank: Pubkey,
+    pub mint: Pubkey,
+    pub cached_price: CachedPrice,
+    pub supply_index: Decimal,
+    pub borrow_index: Decimal,
+}
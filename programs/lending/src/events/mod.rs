@@ -0,0 +1,7 @@
+pub mod admin_events;
+pub mod bank_events;
+pub mod user_events;
+
+pub use admin_events::*;
+pub use bank_events::*;
+pub use user_events::*;
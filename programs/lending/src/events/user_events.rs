@@ -0,0 +1,101 @@
+use crate::decimal::Decimal;
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct InitUserEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct DepositEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub supply_index: Decimal,
+    ///存款后重新计算的健康因子, 无借款时为 Decimal::MAX
+    pub health_factor: Decimal,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    ///健康检查使用的价格, 无借款时不做检查, 为 0
+    pub price: i64,
+    pub price_exponent: i32,
+    pub supply_index: Decimal,
+    pub health_factor: Decimal,
+}
+
+#[event]
+pub struct BorrowEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub price: i64,
+    pub price_exponent: i32,
+    pub borrow_index: Decimal,
+    pub health_factor: Decimal,
+}
+
+#[event]
+pub struct RepayEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub borrow_index: Decimal,
+    ///还款后重新计算的健康因子, 无借款时为 Decimal::MAX
+    pub health_factor: Decimal,
+}
+
+#[event]
+pub struct LiquidateEvent {
+    pub liquidator: Pubkey,
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_bank: Pubkey,
+    pub borrowed_bank: Pubkey,
+    pub repaid_amount: u64,
+    pub repaid_shares: u64,
+    pub seized_amount: u64,
+    pub seized_shares: u64,
+    pub collateral_price: i64,
+    pub collateral_price_exponent: i32,
+    pub borrowed_price: i64,
+    pub borrowed_price_exponent: i32,
+    pub supply_index: Decimal,
+    pub borrow_index: Decimal,
+    ///清算前的健康因子
+    pub previous_health_factor: Decimal,
+    pub health_factor: Decimal,
+}
+
+#[event]
+pub struct LockCollateralEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub bank: Pubkey,
+    pub shares: u64,
+}
+
+#[event]
+pub struct UnlockCollateralEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub bank: Pubkey,
+    pub shares: u64,
+    ///健康检查使用的价格, 无借款时不做检查, 为 0
+    pub price: i64,
+    pub price_exponent: i32,
+    pub health_factor: Decimal,
+}
@@ -0,0 +1,30 @@
+use crate::constants::GLOBAL_CONFIG;
+use crate::errors::ErrorCode;
+use crate::events::AcceptAdminEvent;
+use crate::states::GlobalConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+        has_one = pending_admin @ ErrorCode::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+pub fn accept_admin_handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.admin = global_config.pending_admin;
+    global_config.pending_admin = Pubkey::default();
+
+    emit!(AcceptAdminEvent {
+        admin: global_config.admin,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::errors::ErrorCode;
+use crate::events::AcceptBankAuthorityEvent;
+use crate::states::Bank;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct AcceptBankAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+        has_one = pending_authority @ ErrorCode::Unauthorized,
+    )]
+    pub bank_account: Account<'info, Bank>,
+}
+
+pub fn accept_bank_authority_handler(ctx: Context<AcceptBankAuthority>) -> Result<()> {
+    let bank_account = &mut ctx.accounts.bank_account;
+    bank_account.authority = bank_account.pending_authority;
+    bank_account.pending_authority = Pubkey::default();
+
+    emit!(AcceptBankAuthorityEvent {
+        bank: bank_account.key(),
+        authority: bank_account.authority,
+    });
+
+    Ok(())
+}
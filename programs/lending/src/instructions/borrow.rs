@@ -1,20 +1,31 @@
-use crate::constants::{MAXIMUM_AGE, SOL_USD_FEED_ID, TREASURY, USDC_USD_FEED_ID};
-use crate::enums::MintKind;
+use crate::constants::{GLOBAL_CONFIG, PAUSE_BORROW, TREASURY};
+use crate::decimal::Decimal;
+use crate::enums::Rounding;
 use crate::errors::ErrorCode;
-use crate::float_utils::calc_base_sum_interest;
-use crate::states::{Bank, User};
+use crate::events::BorrowEvent;
+use crate::health_utils::{calc_positions_value, BankPrice};
+use crate::math_utils::{calc_change_shares, calc_token_amount};
+use crate::oracle_utils::get_bank_price;
+use crate::pause_utils::require_not_paused;
+use crate::states::{Bank, GlobalConfig, User};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{
     transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
 };
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 #[derive(Accounts)]
 pub struct Borrow<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -56,63 +67,82 @@ pub struct Borrow<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn borrow_handler(
-    ctx: Context<Borrow>,
-    collateralized_kind: MintKind,
-    to_borrow_kind: MintKind,
-    to_borrow_value: f64,
+///remaining_accounts: 用户其它每个仓位依次传入 bank_account, 价格取自 refresh_bank 的缓存
+pub fn borrow_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Borrow<'info>>,
+    to_borrow_value: Decimal,
 ) -> Result<()> {
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.bank_account,
+        PAUSE_BORROW,
+    )?;
     let user_account = &mut ctx.accounts.user_account;
     let bank_account = &mut ctx.accounts.bank_account;
-    let price_updator = &mut ctx.accounts.price_update;
+    let bank_key = bank_account.key();
     let clock = Clock::get()?;
-    let sol_feed_id = get_feed_id_from_hex(SOL_USD_FEED_ID)?;
-    let sol_price = price_updator.get_price_no_older_than(&clock, MAXIMUM_AGE, &sol_feed_id)?;
-    let usdc_feed_id = get_feed_id_from_hex(USDC_USD_FEED_ID)?;
-    let usdc_price = price_updator.get_price_no_older_than(&clock, MAXIMUM_AGE, &usdc_feed_id)?;
-
-    let borrowed_target_price = match to_borrow_kind {
-        MintKind::SOL => sol_price,
-        MintKind::USDC => usdc_price,
-    };
+    bank_account.accrue_interest(clock.unix_timestamp)?;
+    let borrowed_target_price = get_bank_price(bank_account, &ctx.accounts.price_update, &clock)?;
 
-    let collateralized_value = match collateralized_kind {
-        MintKind::SOL => {
-            let collateralized_amount = user_account.deposited_sol_amount;
-            let collateralized_amount = calc_base_sum_interest(
-                collateralized_amount,
-                bank_account.deposited_interest_ratio,
-                user_account.last_updated,
-                4,
-            );
-            let collateralized_value = collateralized_amount * (sol_price.price as f64);
-            collateralized_value
-        }
-        MintKind::USDC => {
-            let collateralized_amount = user_account.deposited_usdc_amount;
-            let collateralized_amount = calc_base_sum_interest(
-                collateralized_amount,
-                bank_account.deposited_interest_ratio,
-                user_account.last_updated,
-                4,
-            );
-            let collateralized_value = collateralized_amount * usdc_price.price as f64;
-            collateralized_value
-        }
-    };
-    if collateralized_value <= 0.0 {
-        return Err(ErrorCode::DepositedValueLessOrEqualZero.into());
+    let amount = calc_token_amount(
+        to_borrow_value,
+        bank_account.mint_decimals,
+        borrowed_target_price.price,
+        borrowed_target_price.exponent,
+        Rounding::Down,
+    )?;
+    if amount > ctx.accounts.bank_token_account.amount {
+        return Err(ErrorCode::InsufficientLiquidity.into());
     }
+    let new_total_borrowed_amount = bank_account
+        .total_borrowed_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if bank_account.borrow_cap > 0 && new_total_borrowed_amount > bank_account.borrow_cap {
+        return Err(ErrorCode::BorrowCapExceeded.into());
+    }
+    bank_account.record_outflow(
+        amount,
+        ctx.accounts.bank_token_account.amount,
+        clock.unix_timestamp,
+    )?;
+
+    let user_borrowed_shares = calc_change_shares(amount, bank_account.borrow_index, Rounding::Up)?;
+    bank_account.total_borrowed_amount = new_total_borrowed_amount;
+    bank_account.total_borrowed_shares = bank_account
+        .total_borrowed_shares
+        .checked_add(user_borrowed_shares)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let position = user_account.find_or_add_position(&bank_key)?;
+    position.borrowed_shares = position
+        .borrowed_shares
+        .checked_add(user_borrowed_shares)
+        .ok_or(ErrorCode::MathOverflow)?;
 
-    if to_borrow_value > collateralized_value * bank_account.max_ltv {
-        return Err(ErrorCode::NotEnoughLiquidationAssets.into());
+    let positions_value = calc_positions_value(
+        user_account,
+        &[BankPrice {
+            key: bank_key,
+            bank: bank_account,
+            price_update: &ctx.accounts.price_update,
+        }],
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+    if positions_value.ltv_weighted_deposited_value == Decimal::ZERO {
+        return Err(ErrorCode::DepositedValueLessOrEqualZero.into());
     }
+    if !positions_value.is_within_ltv() {
+        return Err(ErrorCode::ExceedMaxLtv.into());
+    }
+    user_account.health_factor = positions_value.health_factor()?;
+    user_account.last_updated = clock.unix_timestamp;
 
     let program = ctx.accounts.token_program.to_account_info();
     let accounts = TransferChecked {
-        from: ctx.accounts.user_token_account.to_account_info(),
+        from: ctx.accounts.bank_token_account.to_account_info(),
         mint: ctx.accounts.mint.to_account_info(),
-        to: ctx.accounts.bank_token_account.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
         authority: ctx.accounts.bank_token_account.to_account_info(),
     };
     let mint_key = ctx.accounts.mint.key();
@@ -123,8 +153,19 @@ pub fn borrow_handler(
     ]];
     let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
     let decimals = ctx.accounts.mint.decimals;
-    let amount = to_borrow_value / borrowed_target_price.price as f64;
-    transfer_checked(cpi_ctx, amount as u64, decimals)?;
+    transfer_checked(cpi_ctx, amount, decimals)?;
+
+    emit!(BorrowEvent {
+        user: ctx.accounts.user_account.key(),
+        owner: ctx.accounts.signer.key(),
+        bank: ctx.accounts.bank_account.key(),
+        amount,
+        shares: user_borrowed_shares,
+        price: borrowed_target_price.price,
+        price_exponent: borrowed_target_price.exponent,
+        borrow_index: ctx.accounts.bank_account.borrow_index,
+        health_factor: ctx.accounts.user_account.health_factor,
+    });
 
     Ok(())
 }
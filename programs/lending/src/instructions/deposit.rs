@@ -1,19 +1,27 @@
+use crate::constants::{COLLATERAL_VAULT, GLOBAL_CONFIG, PAUSE_DEPOSIT, RECEIPT_MINT, TREASURY};
 use crate::errors::ErrorCode;
-use crate::enums::{ShareOp, MintKind};
-use crate::float_utils::calc_new_total_shares;
-use crate::states::{Bank, User};
-use crate::constants::TREASURY;
+use crate::events::DepositEvent;
+use crate::health_utils::{calc_health_factor, BankPrice};
+use crate::pause_utils::require_not_paused;
+use crate::states::{Bank, GlobalConfig, User};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{
-    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+    mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
 };
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -32,6 +40,22 @@ pub struct Deposit<'info> {
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT.as_bytes(), mint.key().as_ref()],
+        bump,
+        token::mint = receipt_mint,
+        token::authority = collateral_vault,
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+
     #[account(
         mut,
         seeds = [signer.key().as_ref()],
@@ -47,6 +71,8 @@ pub struct Deposit<'info> {
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    pub price_update: Account<'info, PriceUpdateV2>,
+
     pub system_program: Program<'info, System>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -54,41 +80,44 @@ pub struct Deposit<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn deposit_handler(
-    ctx: Context<Deposit>,
-    mint_kind: MintKind,
+///remaining_accounts: 有借款时, 用户其它每个仓位依次传入 bank_account, 价格取自 refresh_bank 的缓存
+pub fn deposit_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
     current_deposit_amount: u64,
 ) -> Result<()> {
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.bank_account,
+        PAUSE_DEPOSIT,
+    )?;
     require!(
         current_deposit_amount > 0,
         ErrorCode::DepositedAmountLessOrEqualZero
     );
     let user_account = &mut ctx.accounts.user_account;
     let bank_account = &mut ctx.accounts.bank_account;
-
-    let total_deposited_amount = bank_account.total_deposited_amount;
-    let total_deposited_shares = bank_account.total_deposited_shares;
-    let user_deposited_shares;
-
-    if total_deposited_amount == 0 {
-        user_deposited_shares = current_deposit_amount as f64;
-        bank_account.total_deposited_amount = current_deposit_amount;
-        bank_account.total_deposited_shares = current_deposit_amount as f64;
-    } else {
-        user_deposited_shares = calc_new_total_shares(current_deposit_amount, total_deposited_amount, total_deposited_shares, 4, ShareOp::Increase);
-        bank_account.total_deposited_amount += current_deposit_amount;
-        bank_account.total_deposited_shares = user_deposited_shares;
-    }
-    match mint_kind {
-        MintKind::SOL => {
-            user_account.deposited_sol_amount += current_deposit_amount;
-            user_account.deposited_sol_shares += user_deposited_shares;
-        }
-        MintKind::USDC => {
-            user_account.deposited_usdc_amount += current_deposit_amount;
-            user_account.deposited_usdc_shares += user_deposited_shares;
-        }
-    }
+    let bank_key = bank_account.key();
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+    bank_account.accrue_interest(current_time)?;
+    let user_deposited_shares = bank_account.add_deposit(current_deposit_amount)?;
+    bank_account.record_inflow(current_deposit_amount, current_time);
+    let position = user_account.find_or_add_position(&bank_key)?;
+    position.deposited_shares = position
+        .deposited_shares
+        .checked_add(user_deposited_shares)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_account.health_factor = calc_health_factor(
+        user_account,
+        &[BankPrice {
+            key: bank_key,
+            bank: bank_account,
+            price_update: &ctx.accounts.price_update,
+        }],
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+    user_account.last_updated = current_time;
 
     let accounts = TransferChecked {
         from: ctx.accounts.user_token_account.to_account_info(),
@@ -101,5 +130,31 @@ pub fn deposit_handler(
     let decimals = ctx.accounts.mint.decimals;
     transfer_checked(cpi_ctx, current_deposit_amount, decimals)?;
 
+    //铸造与新增份额等量的存款凭证, 直接锁定在抵押品金库中
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = MintTo {
+        mint: ctx.accounts.receipt_mint.to_account_info(),
+        to: ctx.accounts.collateral_vault.to_account_info(),
+        authority: ctx.accounts.receipt_mint.to_account_info(),
+    };
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        RECEIPT_MINT.as_bytes(),
+        mint_key.as_ref(),
+        &[ctx.bumps.receipt_mint],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    mint_to(cpi_ctx, user_deposited_shares)?;
+
+    emit!(DepositEvent {
+        user: ctx.accounts.user_account.key(),
+        owner: ctx.accounts.signer.key(),
+        bank: ctx.accounts.bank_account.key(),
+        amount: current_deposit_amount,
+        shares: user_deposited_shares,
+        supply_index: ctx.accounts.bank_account.supply_index,
+        health_factor: ctx.accounts.user_account.health_factor,
+    });
+
     Ok(())
 }
@@ -0,0 +1,129 @@
+use crate::constants::{GLOBAL_CONFIG, PAUSE_FLASH_LOAN, TREASURY};
+use crate::errors::ErrorCode;
+use crate::events::FlashBorrowEvent;
+use crate::instruction::ProcessFlashRepay;
+use crate::pause_utils::require_not_paused;
+use crate::states::{Bank, GlobalConfig};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+///FlashRepay 中 bank_account 的账户序号
+const FLASH_REPAY_BANK_ACCOUNT_INDEX: usize = 2;
+
+#[derive(Accounts)]
+pub struct FlashBorrow<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+    )]
+    pub bank_account: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY.as_bytes(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = bank_token_account,
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: instructions sysvar, 由 address 约束
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+///同一交易中当前指令之后必须有针对同一 Bank 的 flash_repay 指令
+pub fn flash_borrow_handler(ctx: Context<FlashBorrow>, amount: u64) -> Result<()> {
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.bank_account,
+        PAUSE_FLASH_LOAN,
+    )?;
+    require!(
+        get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT,
+        ErrorCode::FlashLoanCpiNotAllowed
+    );
+    require!(amount > 0, ErrorCode::DepositedAmountLessOrEqualZero);
+    let bank_account = &mut ctx.accounts.bank_account;
+    let bank_key = bank_account.key();
+    if bank_account.flash_loan_amount > 0 {
+        return Err(ErrorCode::FlashLoanInProgress.into());
+    }
+    if amount > ctx.accounts.bank_token_account.amount {
+        return Err(ErrorCode::InsufficientLiquidity.into());
+    }
+
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)? as usize;
+    let mut index = current_index + 1;
+    let mut has_repay = false;
+    while let Ok(instruction) = load_instruction_at_checked(index, &instructions) {
+        if instruction.program_id == crate::ID
+            && instruction.data.get(..8) == Some(&ProcessFlashRepay::DISCRIMINATOR[..])
+            && instruction
+                .accounts
+                .get(FLASH_REPAY_BANK_ACCOUNT_INDEX)
+                .is_some_and(|account| account.pubkey == bank_key)
+        {
+            has_repay = true;
+            break;
+        }
+        index += 1;
+    }
+    require!(has_repay, ErrorCode::FlashRepayNotFound);
+
+    bank_account.flash_loan_amount = amount;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = TransferChecked {
+        from: ctx.accounts.bank_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.bank_token_account.to_account_info(),
+    };
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        TREASURY.as_bytes(),
+        mint_key.as_ref(),
+        &[ctx.bumps.bank_token_account],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    let decimals = ctx.accounts.mint.decimals;
+    transfer_checked(cpi_ctx, amount, decimals)?;
+
+    emit!(FlashBorrowEvent {
+        bank: ctx.accounts.bank_account.key(),
+        mint: mint_key,
+        borrower: ctx.accounts.signer.key(),
+        amount,
+    });
+
+    Ok(())
+}
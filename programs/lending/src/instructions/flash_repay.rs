@@ -0,0 +1,106 @@
+use crate::constants::TREASURY;
+use crate::decimal::Decimal;
+use crate::enums::Rounding;
+use crate::errors::ErrorCode;
+use crate::events::FlashRepayEvent;
+use crate::math_utils::calc_shares_value;
+use crate::states::Bank;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+///账户顺序变动时需同步修改 flash_borrow 中的 FLASH_REPAY_BANK_ACCOUNT_INDEX
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    pub signer: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+    )]
+    pub bank_account: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY.as_bytes(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = bank_token_account,
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = signer,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+///归还闪电贷本金和手续费, 手续费按 reserve_factor 分给协议, 其余分给存款人
+pub fn flash_repay_handler(ctx: Context<FlashRepay>) -> Result<()> {
+    let bank_account = &mut ctx.accounts.bank_account;
+    let amount = bank_account.flash_loan_amount;
+    if amount == 0 {
+        return Err(ErrorCode::NoFlashLoanInProgress.into());
+    }
+    bank_account.accrue_interest(Clock::get()?.unix_timestamp)?;
+
+    let fee = Decimal::from_u64(amount)
+        .try_mul(bank_account.flash_loan_fee, Rounding::Up)?
+        .try_to_u64(Rounding::Up)?;
+    let protocol_fee = if bank_account.total_deposited_shares == 0 {
+        fee
+    } else {
+        Decimal::from_u64(fee)
+            .try_mul(bank_account.reserve_factor, Rounding::Down)?
+            .try_to_u64(Rounding::Down)?
+    };
+    let depositor_fee = fee - protocol_fee;
+    if depositor_fee > 0 {
+        let index_growth = Decimal::from_u64(depositor_fee)
+            .try_div_u64(bank_account.total_deposited_shares, Rounding::Down)?;
+        bank_account.supply_index = bank_account.supply_index.try_add(index_growth)?;
+        bank_account.total_deposited_amount = calc_shares_value(
+            bank_account.total_deposited_shares,
+            bank_account.supply_index,
+            Rounding::Down,
+        )?;
+    }
+    bank_account.accumulated_protocol_fees = bank_account
+        .accumulated_protocol_fees
+        .checked_add(protocol_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    bank_account.flash_loan_amount = 0;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = TransferChecked {
+        from: ctx.accounts.user_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.bank_token_account.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program, accounts);
+    let decimals = ctx.accounts.mint.decimals;
+    let repay_amount = amount.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+    transfer_checked(cpi_ctx, repay_amount, decimals)?;
+
+    emit!(FlashRepayEvent {
+        bank: ctx.accounts.bank_account.key(),
+        mint: ctx.accounts.mint.key(),
+        borrower: ctx.accounts.signer.key(),
+        amount,
+        fee,
+        protocol_fee,
+        supply_index: ctx.accounts.bank_account.supply_index,
+    });
+
+    Ok(())
+}
@@ -1,14 +1,28 @@
-use crate::constants::{DISCRIMINATOR, TREASURY};
+use crate::config_utils::validate_bank_config;
+use crate::constants::{
+    COLLATERAL_VAULT, DISCRIMINATOR, GLOBAL_CONFIG, RECEIPT_MINT, TREASURY, VIRTUAL_DEPOSIT_VAULT,
+};
+use crate::decimal::Decimal;
 use crate::errors::ErrorCode;
-use crate::states::{Bank, BankBuilder};
+use crate::events::InitBankEvent;
+use crate::math_utils::calc_virtual_deposit_shares;
+use crate::oracle_utils::validate_oracle_config;
+use crate::states::{Bank, BankBuilder, BankConfig, GlobalConfig, OracleConfig};
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct InitBank<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+        constraint = global_config.admin == signer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -30,6 +44,39 @@ pub struct InitBank<'info> {
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        init,
+        payer = signer,
+        seeds = [RECEIPT_MINT.as_bytes(), mint.key().as_ref()],
+        mint::decimals = mint.decimals,
+        mint::authority = receipt_mint,
+        mint::token_program = token_program,
+        bump,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    ///存放已锁定为抵押品的存款凭证
+    #[account(
+        init,
+        payer = signer,
+        seeds = [COLLATERAL_VAULT.as_bytes(), mint.key().as_ref()],
+        token::mint = receipt_mint,
+        token::authority = collateral_vault,
+        bump,
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+
+    ///存放虚拟存款份额对应的存款凭证, 没有指令会以该账户签名, 凭证永久锁定
+    #[account(
+        init,
+        payer = signer,
+        seeds = [VIRTUAL_DEPOSIT_VAULT.as_bytes(), mint.key().as_ref()],
+        token::mint = receipt_mint,
+        token::authority = virtual_deposit_vault,
+        bump,
+    )]
+    pub virtual_deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
     pub system_program: Program<'info, System>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -37,28 +84,66 @@ pub struct InitBank<'info> {
 
 pub fn init_bank_handler(
     ctx: Context<InitBank>,
-    liquidate_threshold: f64,
-    liquidate_bonus: f64,
-    liquidate_close_factor: f64,
-    max_ltv: f64,
-    deposited_interest_ratio: f64,
-    borrowed_interest_ratio: f64,
+    oracle_config: OracleConfig,
+    bank_config: BankConfig,
 ) -> Result<()> {
     msg!("init_bank_handler");
+    validate_oracle_config(&oracle_config)?;
+    validate_bank_config(&bank_config)?;
+    let virtual_deposit_shares = calc_virtual_deposit_shares(ctx.accounts.mint.decimals)?;
     let init_bank = BankBuilder::default()
         .authority(ctx.accounts.signer.key())
         .mint(ctx.accounts.mint.key())
-        .liquidate_threshold(liquidate_threshold)
-        .liquidate_bonus(liquidate_bonus)
-        .liquidate_close_factor(liquidate_close_factor)
-        .max_ltv(max_ltv)
-        .deposited_interest_ratio(deposited_interest_ratio)
-        .borrowed_interest_ratio(borrowed_interest_ratio)
+        .mint_decimals(ctx.accounts.mint.decimals)
+        .receipt_mint(ctx.accounts.receipt_mint.key())
+        .oracle_config(oracle_config)
+        .liquidate_threshold(bank_config.liquidate_threshold)
+        .liquidate_bonus(bank_config.liquidate_bonus)
+        .liquidate_close_factor(bank_config.liquidate_close_factor)
+        .max_ltv(bank_config.max_ltv)
+        .interest_rate_model(bank_config.interest_rate_model)
+        .reserve_factor(bank_config.reserve_factor)
+        .deposit_cap(bank_config.deposit_cap)
+        .borrow_cap(bank_config.borrow_cap)
+        .flash_loan_fee(bank_config.flash_loan_fee)
+        .outflow_window(bank_config.outflow_window)
+        .max_outflow_amount(bank_config.max_outflow_amount)
+        .max_outflow_ratio(bank_config.max_outflow_ratio)
+        .total_deposited_amount(virtual_deposit_shares)
+        .total_deposited_shares(virtual_deposit_shares)
+        .supply_index(Decimal::ONE)
+        .borrow_index(Decimal::ONE)
+        .bump(ctx.bumps.bank_account)
         .last_updated(Clock::get()?.unix_timestamp)
         .build()
         .map_err(|_| ErrorCode::BuilderError)?;
 
     *ctx.accounts.bank_account = init_bank;
 
+    //保持存款凭证总供应量等于 total_deposited_shares
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = MintTo {
+        mint: ctx.accounts.receipt_mint.to_account_info(),
+        to: ctx.accounts.virtual_deposit_vault.to_account_info(),
+        authority: ctx.accounts.receipt_mint.to_account_info(),
+    };
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        RECEIPT_MINT.as_bytes(),
+        mint_key.as_ref(),
+        &[ctx.bumps.receipt_mint],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    mint_to(cpi_ctx, virtual_deposit_shares)?;
+
+    emit!(InitBankEvent {
+        bank: ctx.accounts.bank_account.key(),
+        mint: ctx.accounts.mint.key(),
+        authority: ctx.accounts.signer.key(),
+        receipt_mint: ctx.accounts.receipt_mint.key(),
+        oracle_config,
+        bank_config,
+    });
+
     Ok(())
 }
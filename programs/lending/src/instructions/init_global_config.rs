@@ -0,0 +1,52 @@
+use crate::constants::{DISCRIMINATOR, GLOBAL_CONFIG};
+use crate::errors::ErrorCode;
+use crate::events::InitGlobalConfigEvent;
+use crate::program::Lending;
+use crate::states::{GlobalConfig, GlobalConfigBuilder};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitGlobalConfig<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        space = DISCRIMINATOR + GlobalConfig::INIT_SPACE,
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Lending>,
+
+    ///只有程序的升级权限账户可以初始化全局配置
+    #[account(constraint = program_data.upgrade_authority_address == Some(signer.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_global_config_handler(
+    ctx: Context<InitGlobalConfig>,
+    admin: Pubkey,
+    fee_recipient: Pubkey,
+) -> Result<()> {
+    let init_global_config = GlobalConfigBuilder::default()
+        .admin(admin)
+        .fee_recipient(fee_recipient)
+        .bump(ctx.bumps.global_config)
+        .build()
+        .map_err(|_| ErrorCode::BuilderError)?;
+
+    *ctx.accounts.global_config = init_global_config;
+
+    emit!(InitGlobalConfigEvent {
+        admin,
+        fee_recipient,
+    });
+
+    Ok(())
+}
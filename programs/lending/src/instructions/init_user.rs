@@ -1,5 +1,7 @@
 use crate::constants::DISCRIMINATOR;
+use crate::decimal::Decimal;
 use crate::errors::ErrorCode;
+use crate::events::InitUserEvent;
 use crate::states::{User, UserBuilder};
 use anchor_lang::prelude::*;
 
@@ -23,11 +25,18 @@ pub struct InitUser<'info> {
 pub fn init_user_handler(ctx: Context<InitUser>) -> Result<()> {
     let init_user = UserBuilder::default()
         .owner(ctx.accounts.signer.key())
+        .bump(ctx.bumps.user_account)
+        .health_factor(Decimal::MAX)
         .last_updated(Clock::get()?.unix_timestamp)
         .build()
         .map_err(|_| ErrorCode::BuilderError)?;
 
     *ctx.accounts.user_account = init_user;
 
+    emit!(InitUserEvent {
+        user: ctx.accounts.user_account.key(),
+        owner: ctx.accounts.signer.key(),
+    });
+
     Ok(())
 }
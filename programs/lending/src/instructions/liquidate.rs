@@ -0,0 +1,354 @@
+use crate::constants::{COLLATERAL_VAULT, GLOBAL_CONFIG, PAUSE_LIQUIDATE, RECEIPT_MINT, TREASURY};
+use crate::decimal::Decimal;
+use crate::enums::Rounding;
+use crate::errors::ErrorCode;
+use crate::events::LiquidateEvent;
+use crate::health_utils::{calc_positions_value, BankPrice};
+use crate::math_utils::{
+    calc_change_shares, calc_shares_value, calc_token_amount, calc_token_value,
+};
+use crate::oracle_utils::get_bank_price;
+use crate::pause_utils::require_not_paused;
+use crate::states::{Bank, GlobalConfig, User};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(constraint = borrowed_mint.key() != collateral_mint.key() @ ErrorCode::NotLiquidatable)]
+    pub borrowed_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [collateral_mint.key().as_ref()],
+        bump = collateral_bank_account.bump,
+    )]
+    pub collateral_bank_account: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY.as_bytes(), collateral_mint.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = collateral_bank_token_account
+    )]
+    pub collateral_bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT.as_bytes(), collateral_mint.key().as_ref()],
+        bump,
+    )]
+    pub collateral_receipt_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT.as_bytes(), collateral_mint.key().as_ref()],
+        bump,
+        token::mint = collateral_receipt_mint,
+        token::authority = collateral_vault,
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [borrowed_mint.key().as_ref()],
+        bump = borrowed_bank_account.bump,
+    )]
+    pub borrowed_bank_account: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY.as_bytes(), borrowed_mint.key().as_ref()],
+        bump,
+        token::mint = borrowed_mint,
+        token::authority = borrowed_bank_token_account
+    )]
+    pub borrowed_bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [user_account.owner.as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, User>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+    )]
+    pub liquidator_collateral_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = borrowed_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+    )]
+    pub liquidator_borrowed_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub collateral_price_update: Account<'info, PriceUpdateV2>,
+
+    pub borrowed_price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+///remaining_accounts: 用户其它每个仓位依次传入 bank_account, 价格取自 refresh_bank 的缓存
+pub fn liquidate_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Liquidate<'info>>,
+    to_repay_amount: u64,
+) -> Result<()> {
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.collateral_bank_account,
+        PAUSE_LIQUIDATE,
+    )?;
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.borrowed_bank_account,
+        PAUSE_LIQUIDATE,
+    )?;
+    let user_account = &mut ctx.accounts.user_account;
+    let collateral_bank_account = &mut ctx.accounts.collateral_bank_account;
+    let borrowed_bank_account = &mut ctx.accounts.borrowed_bank_account;
+    let collateral_bank_key = collateral_bank_account.key();
+    let borrowed_bank_key = borrowed_bank_account.key();
+    let clock = Clock::get()?;
+    collateral_bank_account.accrue_interest(clock.unix_timestamp)?;
+    borrowed_bank_account.accrue_interest(clock.unix_timestamp)?;
+    let collateral_price = get_bank_price(
+        collateral_bank_account,
+        &ctx.accounts.collateral_price_update,
+        &clock,
+    )?;
+    let borrowed_price = get_bank_price(
+        borrowed_bank_account,
+        &ctx.accounts.borrowed_price_update,
+        &clock,
+    )?;
+
+    let user_collateral_shares = user_account
+        .find_position(&collateral_bank_key)
+        .map_or(0, |position| position.deposited_shares);
+    let user_borrowed_shares = user_account
+        .find_position(&borrowed_bank_key)
+        .map_or(0, |position| position.borrowed_shares);
+    if user_borrowed_shares == 0 {
+        return Err(ErrorCode::NotLiquidatable.into());
+    }
+
+    let positions_value = calc_positions_value(
+        user_account,
+        &[
+            BankPrice {
+                key: collateral_bank_key,
+                bank: collateral_bank_account,
+                price_update: &ctx.accounts.collateral_price_update,
+            },
+            BankPrice {
+                key: borrowed_bank_key,
+                bank: borrowed_bank_account,
+                price_update: &ctx.accounts.borrowed_price_update,
+            },
+        ],
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+    if !positions_value.is_liquidatable()? {
+        return Err(ErrorCode::NotLiquidatable.into());
+    }
+    let previous_health_factor = positions_value.health_factor()?;
+
+    let user_collateral_amount = calc_shares_value(
+        user_collateral_shares,
+        collateral_bank_account.supply_index,
+        Rounding::Down,
+    )?;
+    let user_borrowed_amount = calc_shares_value(
+        user_borrowed_shares,
+        borrowed_bank_account.borrow_index,
+        Rounding::Up,
+    )?;
+
+    let max_repay_amount = Decimal::from_u64(user_borrowed_amount)
+        .try_mul(borrowed_bank_account.liquidate_close_factor, Rounding::Down)?;
+    if Decimal::from_u64(to_repay_amount) > max_repay_amount {
+        return Err(ErrorCode::LiquidateExceedCloseFactor.into());
+    }
+
+    let to_seize_value = calc_token_value(
+        to_repay_amount,
+        borrowed_bank_account.mint_decimals,
+        borrowed_price.price,
+        borrowed_price.exponent,
+        Rounding::Down,
+    )?
+    .try_mul(
+        Decimal::ONE.try_add(collateral_bank_account.liquidate_bonus)?,
+        Rounding::Down,
+    )?;
+    let to_seize_amount = calc_token_amount(
+        to_seize_value,
+        collateral_bank_account.mint_decimals,
+        collateral_price.price,
+        collateral_price.exponent,
+        Rounding::Down,
+    )?;
+    if to_seize_amount > user_collateral_amount {
+        return Err(ErrorCode::NotEnoughLiquidationAssets.into());
+    }
+
+    collateral_bank_account.record_outflow(
+        to_seize_amount,
+        ctx.accounts.collateral_bank_token_account.amount,
+        clock.unix_timestamp,
+    )?;
+    borrowed_bank_account.record_inflow(to_repay_amount, clock.unix_timestamp);
+
+    let repaid_shares = calc_change_shares(
+        to_repay_amount,
+        borrowed_bank_account.borrow_index,
+        Rounding::Down,
+    )?
+    .min(user_borrowed_shares);
+    let seized_shares = calc_change_shares(
+        to_seize_amount,
+        collateral_bank_account.supply_index,
+        Rounding::Up,
+    )?
+    .min(user_collateral_shares);
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = TransferChecked {
+        from: ctx
+            .accounts
+            .liquidator_borrowed_token_account
+            .to_account_info(),
+        mint: ctx.accounts.borrowed_mint.to_account_info(),
+        to: ctx.accounts.borrowed_bank_token_account.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program, accounts);
+    let decimals = ctx.accounts.borrowed_mint.decimals;
+    transfer_checked(cpi_ctx, to_repay_amount, decimals)?;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = TransferChecked {
+        from: ctx.accounts.collateral_bank_token_account.to_account_info(),
+        mint: ctx.accounts.collateral_mint.to_account_info(),
+        to: ctx
+            .accounts
+            .liquidator_collateral_token_account
+            .to_account_info(),
+        authority: ctx.accounts.collateral_bank_token_account.to_account_info(),
+    };
+    let collateral_mint_key = ctx.accounts.collateral_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        TREASURY.as_bytes(),
+        collateral_mint_key.as_ref(),
+        &[ctx.bumps.collateral_bank_token_account],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    let decimals = ctx.accounts.collateral_mint.decimals;
+    transfer_checked(cpi_ctx, to_seize_amount, decimals)?;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = Burn {
+        mint: ctx.accounts.collateral_receipt_mint.to_account_info(),
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        COLLATERAL_VAULT.as_bytes(),
+        collateral_mint_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    burn(cpi_ctx, seized_shares)?;
+
+    borrowed_bank_account.total_borrowed_amount = borrowed_bank_account
+        .total_borrowed_amount
+        .saturating_sub(to_repay_amount);
+    borrowed_bank_account.total_borrowed_shares = borrowed_bank_account
+        .total_borrowed_shares
+        .saturating_sub(repaid_shares);
+    collateral_bank_account.total_deposited_amount = collateral_bank_account
+        .total_deposited_amount
+        .saturating_sub(to_seize_amount);
+    collateral_bank_account.total_deposited_shares = collateral_bank_account
+        .total_deposited_shares
+        .saturating_sub(seized_shares);
+
+    if let Some(position) = user_account.find_position_mut(&borrowed_bank_key) {
+        position.borrowed_shares -= repaid_shares;
+    }
+    if let Some(position) = user_account.find_position_mut(&collateral_bank_key) {
+        position.deposited_shares -= seized_shares;
+    }
+    user_account.remove_empty_positions();
+
+    let positions_value = calc_positions_value(
+        user_account,
+        &[
+            BankPrice {
+                key: collateral_bank_key,
+                bank: collateral_bank_account,
+                price_update: &ctx.accounts.collateral_price_update,
+            },
+            BankPrice {
+                key: borrowed_bank_key,
+                bank: borrowed_bank_account,
+                price_update: &ctx.accounts.borrowed_price_update,
+            },
+        ],
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+    user_account.health_factor = positions_value.health_factor()?;
+    user_account.last_updated = clock.unix_timestamp;
+
+    emit!(LiquidateEvent {
+        liquidator: ctx.accounts.signer.key(),
+        user: user_account.key(),
+        owner: user_account.owner,
+        collateral_bank: collateral_bank_key,
+        borrowed_bank: borrowed_bank_key,
+        repaid_amount: to_repay_amount,
+        repaid_shares,
+        seized_amount: to_seize_amount,
+        seized_shares,
+        collateral_price: collateral_price.price,
+        collateral_price_exponent: collateral_price.exponent,
+        borrowed_price: borrowed_price.price,
+        borrowed_price_exponent: borrowed_price.exponent,
+        supply_index: collateral_bank_account.supply_index,
+        borrow_index: borrowed_bank_account.borrow_index,
+        previous_health_factor,
+        health_factor: user_account.health_factor,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use crate::constants::{COLLATERAL_VAULT, GLOBAL_CONFIG, PAUSE_DEPOSIT, RECEIPT_MINT};
+use crate::errors::ErrorCode;
+use crate::events::LockCollateralEvent;
+use crate::health_utils::{calc_health_factor, BankPrice};
+use crate::pause_utils::require_not_paused;
+use crate::states::{Bank, GlobalConfig, User};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct LockCollateral<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+    )]
+    pub bank_account: Account<'info, Bank>,
+
+    #[account(
+        seeds = [RECEIPT_MINT.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT.as_bytes(), mint.key().as_ref()],
+        bump,
+        token::mint = receipt_mint,
+        token::authority = collateral_vault,
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, User>,
+
+    #[account(
+        mut,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+    )]
+    pub user_receipt_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+///将钱包中的存款凭证锁定为抵押品, 计入用户仓位, 与存款共用暂停标志
+///
+///remaining_accounts: 有借款时, 用户其它每个仓位依次传入 bank_account, 价格取自 refresh_bank 的缓存
+pub fn lock_collateral_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, LockCollateral<'info>>,
+    shares: u64,
+) -> Result<()> {
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.bank_account,
+        PAUSE_DEPOSIT,
+    )?;
+    require!(shares > 0, ErrorCode::DepositedAmountLessOrEqualZero);
+    let user_account = &mut ctx.accounts.user_account;
+    let bank_account = &mut ctx.accounts.bank_account;
+    let bank_key = bank_account.key();
+    let clock = Clock::get()?;
+    bank_account.accrue_interest(clock.unix_timestamp)?;
+    let position = user_account.find_or_add_position(&bank_key)?;
+    position.deposited_shares = position
+        .deposited_shares
+        .checked_add(shares)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_account.health_factor = calc_health_factor(
+        user_account,
+        &[BankPrice {
+            key: bank_key,
+            bank: bank_account,
+            price_update: &ctx.accounts.price_update,
+        }],
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+    user_account.last_updated = clock.unix_timestamp;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = TransferChecked {
+        from: ctx.accounts.user_receipt_token_account.to_account_info(),
+        mint: ctx.accounts.receipt_mint.to_account_info(),
+        to: ctx.accounts.collateral_vault.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program, accounts);
+    let decimals = ctx.accounts.receipt_mint.decimals;
+    transfer_checked(cpi_ctx, shares, decimals)?;
+
+    emit!(LockCollateralEvent {
+        user: ctx.accounts.user_account.key(),
+        owner: ctx.accounts.signer.key(),
+        bank: ctx.accounts.bank_account.key(),
+        shares,
+    });
+
+    Ok(())
+}
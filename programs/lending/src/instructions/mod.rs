@@ -1,13 +1,47 @@
+pub mod accept_admin;
+pub mod accept_bank_authority;
 pub mod borrow;
 pub mod deposit;
+pub mod flash_borrow;
+pub mod flash_repay;
 pub mod init_bank;
+pub mod init_global_config;
 pub mod init_user;
+pub mod liquidate;
+pub mod lock_collateral;
+pub mod propose_admin;
+pub mod propose_bank_authority;
+pub mod refresh_bank;
 pub mod repay;
+pub mod set_fee_recipient;
+pub mod set_guardian;
+pub mod set_pause_flags;
+pub mod unlock_collateral;
+pub mod update_bank_config;
+pub mod update_bank_oracle;
 pub mod withdraw;
+pub mod withdraw_protocol_fees;
 
+pub use accept_admin::*;
+pub use accept_bank_authority::*;
 pub use borrow::*;
 pub use deposit::*;
+pub use flash_borrow::*;
+pub use flash_repay::*;
 pub use init_bank::*;
+pub use init_global_config::*;
 pub use init_user::*;
+pub use liquidate::*;
+pub use lock_collateral::*;
+pub use propose_admin::*;
+pub use propose_bank_authority::*;
+pub use refresh_bank::*;
 pub use repay::*;
+pub use set_fee_recipient::*;
+pub use set_guardian::*;
+pub use set_pause_flags::*;
+pub use unlock_collateral::*;
+pub use update_bank_config::*;
+pub use update_bank_oracle::*;
 pub use withdraw::*;
+pub use withdraw_protocol_fees::*;
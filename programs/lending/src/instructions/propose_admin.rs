@@ -0,0 +1,30 @@
+use crate::constants::GLOBAL_CONFIG;
+use crate::errors::ErrorCode;
+use crate::events::ProposeAdminEvent;
+use crate::states::GlobalConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+///new_admin 为 Pubkey::default() 时取消待接受的转移
+pub fn propose_admin_handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    ctx.accounts.global_config.pending_admin = new_admin;
+
+    emit!(ProposeAdminEvent {
+        admin: ctx.accounts.admin.key(),
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
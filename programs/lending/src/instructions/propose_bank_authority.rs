@@ -0,0 +1,36 @@
+use crate::errors::ErrorCode;
+use crate::events::ProposeBankAuthorityEvent;
+use crate::states::Bank;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct ProposeBankAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub bank_account: Account<'info, Bank>,
+}
+
+///new_authority 为 Pubkey::default() 时取消待接受的转移
+pub fn propose_bank_authority_handler(
+    ctx: Context<ProposeBankAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    ctx.accounts.bank_account.pending_authority = new_authority;
+
+    emit!(ProposeBankAuthorityEvent {
+        bank: ctx.accounts.bank_account.key(),
+        authority: ctx.accounts.authority.key(),
+        pending_authority: new_authority,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::errors::ErrorCode;
+use crate::events::RefreshBankEvent;
+use crate::oracle_utils::get_bank_price;
+use crate::states::{Bank, CachedPrice};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct RefreshBank<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+    )]
+    pub bank_account: Account<'info, Bank>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+}
+
+///无需权限: 结算利息并缓存最新的预言机价格
+pub fn refresh_bank_handler(ctx: Context<RefreshBank>) -> Result<()> {
+    let bank_account = &mut ctx.accounts.bank_account;
+    let clock = Clock::get()?;
+    bank_account.accrue_interest(clock.unix_timestamp)?;
+
+    let price = get_bank_price(bank_account, &ctx.accounts.price_update, &clock)?;
+    require!(price.price > 0, ErrorCode::InvalidPrice);
+    bank_account.cached_price = CachedPrice {
+        price: price.price,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
+    };
+
+    emit!(RefreshBankEvent {
+        bank: bank_account.key(),
+        mint: ctx.accounts.mint.key(),
+        cached_price: bank_account.cached_price,
+        supply_index: bank_account.supply_index,
+        borrow_index: bank_account.borrow_index,
+    });
+
+    Ok(())
+}
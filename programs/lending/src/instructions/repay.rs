@@ -1,19 +1,29 @@
-use crate::constants::TREASURY;
-use crate::enums::MintKind;
+use crate::constants::{GLOBAL_CONFIG, PAUSE_REPAY, TREASURY};
+use crate::enums::Rounding;
 use crate::errors::ErrorCode;
-use crate::float_utils::{calc_base_sum_interest, calc_change_shares};
-use crate::states::{Bank, User};
+use crate::events::RepayEvent;
+use crate::health_utils::{calc_health_factor, BankPrice};
+use crate::math_utils::{calc_change_shares, calc_shares_value};
+use crate::pause_utils::require_not_paused;
+use crate::states::{Bank, GlobalConfig, User};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{
     transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
 };
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 #[derive(Accounts)]
 pub struct Repay<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -47,6 +57,8 @@ pub struct Repay<'info> {
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    pub price_update: Account<'info, PriceUpdateV2>,
+
     pub system_program: Program<'info, System>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -54,46 +66,45 @@ pub struct Repay<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn repay_handler(
-    ctx: Context<Repay>,
-    repay_kind: MintKind,
+///remaining_accounts: 部分还款后仍有借款时, 用户其它每个仓位依次传入 bank_account, 价格取自 refresh_bank 的缓存
+pub fn repay_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Repay<'info>>,
     to_repay_amount: u64,
 ) -> Result<()> {
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.bank_account,
+        PAUSE_REPAY,
+    )?;
     let user_account = &mut ctx.accounts.user_account;
     let bank_account = &mut ctx.accounts.bank_account;
-    let user_borrowed_amount = match repay_kind {
-        MintKind::SOL => {
-            if user_account.borrowed_sol_amount == 0 {
-                return Err(ErrorCode::NoNeedToRepay.into());
-            }
-            user_account.borrowed_sol_amount
-        }
-        MintKind::USDC => {
-            if user_account.borrowed_usdc_amount == 0 {
-                return Err(ErrorCode::NoNeedToRepay.into());
-            }
-            user_account.borrowed_usdc_amount
-        }
-    };
+    let bank_key = bank_account.key();
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+    bank_account.accrue_interest(current_time)?;
+
+    let user_borrowed_shares = user_account
+        .find_position(&bank_account.key())
+        .map_or(0, |position| position.borrowed_shares);
+    if user_borrowed_shares == 0 {
+        return Err(ErrorCode::NoNeedToRepay.into());
+    }
 
-    let changed_shares = calc_change_shares(
-        to_repay_amount,
-        user_borrowed_amount,
-        bank_account.total_borrowed_shares,
-        4,
-    );
-
-    let user_borrowed_amount = calc_base_sum_interest(
-        user_borrowed_amount,
-        bank_account.borrowed_interest_ratio,
-        user_account.last_updated,
-        4,
-    );
-
-    if to_repay_amount as f64 > user_borrowed_amount {
+    let user_borrowed_amount = calc_shares_value(
+        user_borrowed_shares,
+        bank_account.borrow_index,
+        Rounding::Up,
+    )?;
+    if to_repay_amount > user_borrowed_amount {
         return Err(ErrorCode::RepayExceedBorrowed.into());
     }
 
+    let changed_shares = if to_repay_amount == user_borrowed_amount {
+        user_borrowed_shares
+    } else {
+        calc_change_shares(to_repay_amount, bank_account.borrow_index, Rounding::Down)?
+    };
+
     let program = ctx.accounts.token_program.to_account_info();
     let accounts = TransferChecked {
         from: ctx.accounts.user_token_account.to_account_info(),
@@ -105,11 +116,38 @@ pub fn repay_handler(
     let decimals = ctx.accounts.mint.decimals;
     transfer_checked(cpi_ctx, to_repay_amount, decimals)?;
 
-    bank_account.total_borrowed_shares -= changed_shares;
-    match repay_kind {
-        MintKind::SOL => user_account.borrowed_sol_shares -= changed_shares,
-        MintKind::USDC => user_account.borrowed_usdc_shares -= changed_shares,
+    bank_account.total_borrowed_amount = bank_account
+        .total_borrowed_amount
+        .saturating_sub(to_repay_amount);
+    bank_account.total_borrowed_shares = bank_account
+        .total_borrowed_shares
+        .saturating_sub(changed_shares);
+    bank_account.record_inflow(to_repay_amount, current_time);
+    if let Some(position) = user_account.find_position_mut(&bank_account.key()) {
+        position.borrowed_shares -= changed_shares;
     }
+    user_account.remove_empty_positions();
+    user_account.health_factor = calc_health_factor(
+        user_account,
+        &[BankPrice {
+            key: bank_key,
+            bank: bank_account,
+            price_update: &ctx.accounts.price_update,
+        }],
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+    user_account.last_updated = current_time;
+
+    emit!(RepayEvent {
+        user: user_account.key(),
+        owner: user_account.owner,
+        bank: bank_account.key(),
+        amount: to_repay_amount,
+        shares: changed_shares,
+        borrow_index: bank_account.borrow_index,
+        health_factor: user_account.health_factor,
+    });
 
     Ok(())
 }
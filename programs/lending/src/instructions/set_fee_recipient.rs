@@ -0,0 +1,29 @@
+use crate::constants::GLOBAL_CONFIG;
+use crate::errors::ErrorCode;
+use crate::events::SetFeeRecipientEvent;
+use crate::states::GlobalConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetFeeRecipient<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+pub fn set_fee_recipient_handler(
+    ctx: Context<SetFeeRecipient>,
+    fee_recipient: Pubkey,
+) -> Result<()> {
+    ctx.accounts.global_config.fee_recipient = fee_recipient;
+
+    emit!(SetFeeRecipientEvent { fee_recipient });
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::constants::GLOBAL_CONFIG;
+use crate::errors::ErrorCode;
+use crate::events::SetGuardianEvent;
+use crate::states::GlobalConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+///guardian 为 Pubkey::default() 时撤销 guardian
+pub fn set_guardian_handler(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    ctx.accounts.global_config.guardian = guardian;
+
+    emit!(SetGuardianEvent { guardian });
+
+    Ok(())
+}
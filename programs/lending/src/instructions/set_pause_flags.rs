@@ -0,0 +1,60 @@
+use crate::constants::GLOBAL_CONFIG;
+use crate::errors::ErrorCode;
+use crate::events::SetPauseFlagsEvent;
+use crate::pause_utils::validate_pause_flags;
+use crate::states::{Bank, GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPauseFlags<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    ///不传时设置全局暂停标志位
+    #[account(
+        mut,
+        seeds = [bank_account.mint.as_ref()],
+        bump = bank_account.bump,
+    )]
+    pub bank_account: Option<Account<'info, Bank>>,
+}
+
+///authority(Bank 的 authority 或全局管理员)可以任意设置, guardian 只能暂停
+pub fn set_pause_flags_handler(ctx: Context<SetPauseFlags>, pause_flags: u64) -> Result<()> {
+    let signer_key = ctx.accounts.signer.key();
+    let global_config = &mut ctx.accounts.global_config;
+    let is_guardian = global_config.guardian == signer_key;
+
+    match ctx.accounts.bank_account.as_mut() {
+        Some(bank_account) => {
+            let is_authority = bank_account.authority == signer_key;
+            require!(is_authority || is_guardian, ErrorCode::Unauthorized);
+            validate_pause_flags(bank_account.pause_flags, pause_flags, is_authority)?;
+            bank_account.pause_flags = pause_flags;
+        }
+        None => {
+            let is_authority = global_config.admin == signer_key;
+            require!(is_authority || is_guardian, ErrorCode::Unauthorized);
+            validate_pause_flags(global_config.pause_flags, pause_flags, is_authority)?;
+            global_config.pause_flags = pause_flags;
+        }
+    }
+
+    emit!(SetPauseFlagsEvent {
+        signer: signer_key,
+        bank: ctx
+            .accounts
+            .bank_account
+            .as_ref()
+            .map(|bank_account| bank_account.key()),
+        pause_flags,
+    });
+
+    Ok(())
+}
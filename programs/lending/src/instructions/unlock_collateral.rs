@@ -0,0 +1,156 @@
+use crate::constants::{COLLATERAL_VAULT, GLOBAL_CONFIG, PAUSE_WITHDRAW, RECEIPT_MINT};
+use crate::decimal::Decimal;
+use crate::errors::ErrorCode;
+use crate::events::UnlockCollateralEvent;
+use crate::health_utils::{calc_positions_value, BankPrice};
+use crate::oracle_utils::get_bank_price;
+use crate::pause_utils::require_not_paused;
+use crate::states::{Bank, GlobalConfig, User};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct UnlockCollateral<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+    )]
+    pub bank_account: Account<'info, Bank>,
+
+    #[account(
+        seeds = [RECEIPT_MINT.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT.as_bytes(), mint.key().as_ref()],
+        bump,
+        token::mint = receipt_mint,
+        token::authority = collateral_vault,
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, User>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+    )]
+    pub user_receipt_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+///将锁定的存款凭证取回钱包, 解锁后不再计入抵押品
+///
+///remaining_accounts: 用户其它每个仓位依次传入 bank_account, 价格取自 refresh_bank 的缓存
+pub fn unlock_collateral_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnlockCollateral<'info>>,
+    shares: u64,
+) -> Result<()> {
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.bank_account,
+        PAUSE_WITHDRAW,
+    )?;
+    let user_account = &mut ctx.accounts.user_account;
+    let bank_account = &mut ctx.accounts.bank_account;
+    let bank_key = bank_account.key();
+    let clock = Clock::get()?;
+    bank_account.accrue_interest(clock.unix_timestamp)?;
+
+    let user_deposited_shares = user_account
+        .find_position(&bank_key)
+        .map_or(0, |position| position.deposited_shares);
+    if shares == 0 || shares > user_deposited_shares {
+        return Err(ErrorCode::NotEnoughBalance.into());
+    }
+    if let Some(position) = user_account.find_position_mut(&bank_key) {
+        position.deposited_shares -= shares;
+    }
+
+    let (mut price, mut price_exponent) = (0, 0);
+    if user_account.has_borrowed() {
+        let bank_price = get_bank_price(bank_account, &ctx.accounts.price_update, &clock)?;
+        (price, price_exponent) = (bank_price.price, bank_price.exponent);
+        let positions_value = calc_positions_value(
+            user_account,
+            &[BankPrice {
+                key: bank_key,
+                bank: bank_account,
+                price_update: &ctx.accounts.price_update,
+            }],
+            ctx.remaining_accounts,
+            &clock,
+        )?;
+        if !positions_value.is_within_ltv() {
+            return Err(ErrorCode::ExceedMaxLtv.into());
+        }
+        user_account.health_factor = positions_value.health_factor()?;
+    } else {
+        user_account.health_factor = Decimal::MAX;
+    }
+    user_account.remove_empty_positions();
+    user_account.last_updated = clock.unix_timestamp;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = TransferChecked {
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        mint: ctx.accounts.receipt_mint.to_account_info(),
+        to: ctx.accounts.user_receipt_token_account.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        COLLATERAL_VAULT.as_bytes(),
+        mint_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    let decimals = ctx.accounts.receipt_mint.decimals;
+    transfer_checked(cpi_ctx, shares, decimals)?;
+
+    emit!(UnlockCollateralEvent {
+        user: ctx.accounts.user_account.key(),
+        owner: ctx.accounts.signer.key(),
+        bank: ctx.accounts.bank_account.key(),
+        shares,
+        price,
+        price_exponent,
+        health_factor: ctx.accounts.user_account.health_factor,
+    });
+
+    Ok(())
+}
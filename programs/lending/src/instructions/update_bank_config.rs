@@ -0,0 +1,40 @@
+use crate::config_utils::validate_bank_config;
+use crate::errors::ErrorCode;
+use crate::events::UpdateBankConfigEvent;
+use crate::states::{Bank, BankConfig};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct UpdateBankConfig<'info> {
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub bank_account: Account<'info, Bank>,
+}
+
+pub fn update_bank_config_handler(
+    ctx: Context<UpdateBankConfig>,
+    bank_config: BankConfig,
+) -> Result<()> {
+    validate_bank_config(&bank_config)?;
+    let bank_account = &mut ctx.accounts.bank_account;
+    //先按旧利率模型结算利息, 新利率只对此后的时间生效
+    bank_account.accrue_interest(Clock::get()?.unix_timestamp)?;
+    bank_account.set_config(&bank_config);
+
+    emit!(UpdateBankConfigEvent {
+        bank: bank_account.key(),
+        mint: ctx.accounts.mint.key(),
+        bank_config,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::errors::ErrorCode;
+use crate::events::UpdateBankOracleEvent;
+use crate::oracle_utils::validate_oracle_config;
+use crate::states::{Bank, CachedPrice, OracleConfig};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct UpdateBankOracle<'info> {
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub bank_account: Account<'info, Bank>,
+}
+
+pub fn update_bank_oracle_handler(
+    ctx: Context<UpdateBankOracle>,
+    oracle_config: OracleConfig,
+) -> Result<()> {
+    validate_oracle_config(&oracle_config)?;
+    ctx.accounts.bank_account.oracle_config = oracle_config;
+    ctx.accounts.bank_account.cached_price = CachedPrice::default();
+
+    emit!(UpdateBankOracleEvent {
+        bank: ctx.accounts.bank_account.key(),
+        mint: ctx.accounts.mint.key(),
+        oracle_config,
+    });
+
+    Ok(())
+}
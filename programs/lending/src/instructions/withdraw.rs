@@ -1,17 +1,29 @@
-use crate::constants::TREASURY;
-use crate::enums::MintKind;
+use crate::constants::{COLLATERAL_VAULT, GLOBAL_CONFIG, PAUSE_WITHDRAW, RECEIPT_MINT, TREASURY};
+use crate::decimal::Decimal;
 use crate::errors::ErrorCode;
-use crate::float_utils::{calc_base_sum_interest, calc_change_shares};
-use crate::states::{Bank, User};
+use crate::events::WithdrawEvent;
+use crate::health_utils::{calc_positions_value, BankPrice};
+use crate::oracle_utils::get_bank_price;
+use crate::pause_utils::require_not_paused;
+use crate::states::{Bank, GlobalConfig, User};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{
+    burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 #[derive(Accounts)]
 pub struct WithDraw<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -30,6 +42,22 @@ pub struct WithDraw<'info> {
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT.as_bytes(), mint.key().as_ref()],
+        bump,
+        token::mint = receipt_mint,
+        token::authority = collateral_vault,
+    )]
+    pub collateral_vault: InterfaceAccount<'info, TokenAccount>,
+
     #[account(
         mut,
         seeds = [signer.key().as_ref()],
@@ -46,6 +74,8 @@ pub struct WithDraw<'info> {
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    pub price_update: Account<'info, PriceUpdateV2>,
+
     pub system_program: Program<'info, System>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -53,37 +83,102 @@ pub struct WithDraw<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn withdraw_handler(ctx: Context<WithDraw>, mint_kind: MintKind, amount: u64) -> Result<()> {
+///remaining_accounts: 用户其它每个仓位依次传入 bank_account, 价格取自 refresh_bank 的缓存
+pub fn withdraw_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithDraw<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require_not_paused(
+        &ctx.accounts.global_config,
+        &ctx.accounts.bank_account,
+        PAUSE_WITHDRAW,
+    )?;
     let user_account = &mut ctx.accounts.user_account;
     let bank_account = &mut ctx.accounts.bank_account;
-    let bank_total_deposited = bank_account.total_deposited_amount;
-    let bank_total_shares = bank_account.total_deposited_shares;
-
-    let user_deposited_amount_without_interest = match mint_kind {
-        MintKind::SOL => {
-            if user_account.deposited_sol_amount <= 0 {
-                return Err(ErrorCode::NotEnoughBalance.into());
-            }
-            user_account.deposited_sol_amount
-        }
-        MintKind::USDC => {
-            if user_account.deposited_usdc_amount <= 0 {
-                return Err(ErrorCode::NotEnoughBalance.into());
-            }
-            user_account.deposited_usdc_amount
+    let bank_key = bank_account.key();
+    let clock = Clock::get()?;
+    bank_account.accrue_interest(clock.unix_timestamp)?;
+
+    let user_deposited_shares = user_account
+        .find_position(&bank_key)
+        .map_or(0, |position| position.deposited_shares);
+    let change_shares = bank_account.remove_deposit(amount, user_deposited_shares)?;
+    bank_account.record_outflow(
+        amount,
+        ctx.accounts.bank_token_account.amount,
+        clock.unix_timestamp,
+    )?;
+
+    if let Some(position) = user_account.find_position_mut(&bank_key) {
+        position.deposited_shares -= change_shares;
+    }
+
+    let (mut price, mut price_exponent) = (0, 0);
+    if user_account.has_borrowed() {
+        let bank_price = get_bank_price(bank_account, &ctx.accounts.price_update, &clock)?;
+        (price, price_exponent) = (bank_price.price, bank_price.exponent);
+        let positions_value = calc_positions_value(
+            user_account,
+            &[BankPrice {
+                key: bank_key,
+                bank: bank_account,
+                price_update: &ctx.accounts.price_update,
+            }],
+            ctx.remaining_accounts,
+            &clock,
+        )?;
+        if !positions_value.is_within_ltv() {
+            return Err(ErrorCode::ExceedMaxLtv.into());
         }
-    };
-    let user_deposited_amount_with_interest = calc_base_sum_interest(
-        user_deposited_amount_without_interest,
-        bank_account.deposited_interest_ratio,
-        user_account.last_updated,
-        4,
-    );
-    if amount as f64 > user_deposited_amount_with_interest {
-        return Err(ErrorCode::NotEnoughBalance.into()); 
+        user_account.health_factor = positions_value.health_factor()?;
+    } else {
+        user_account.health_factor = Decimal::MAX;
     }
+    user_account.remove_empty_positions();
+    user_account.last_updated = clock.unix_timestamp;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = TransferChecked {
+        from: ctx.accounts.bank_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.bank_token_account.to_account_info(),
+    };
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        TREASURY.as_bytes(),
+        mint_key.as_ref(),
+        &[ctx.bumps.bank_token_account],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    let decimals = ctx.accounts.mint.decimals;
+    transfer_checked(cpi_ctx, amount, decimals)?;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = Burn {
+        mint: ctx.accounts.receipt_mint.to_account_info(),
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        COLLATERAL_VAULT.as_bytes(),
+        mint_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    burn(cpi_ctx, change_shares)?;
 
-    let change_shares = calc_change_shares(amount, bank_total_deposited, bank_total_shares, 4);
+    emit!(WithdrawEvent {
+        user: ctx.accounts.user_account.key(),
+        owner: ctx.accounts.signer.key(),
+        bank: ctx.accounts.bank_account.key(),
+        amount,
+        shares: change_shares,
+        price,
+        price_exponent,
+        supply_index: ctx.accounts.bank_account.supply_index,
+        health_factor: ctx.accounts.user_account.health_factor,
+    });
 
     Ok(())
 }
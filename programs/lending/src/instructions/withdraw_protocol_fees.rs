@@ -0,0 +1,101 @@
+use crate::constants::{GLOBAL_CONFIG, TREASURY};
+use crate::errors::ErrorCode;
+use crate::events::WithdrawProtocolFeesEvent;
+use crate::states::{Bank, GlobalConfig};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG.as_bytes()],
+        bump = global_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+        has_one = fee_recipient @ ErrorCode::Unauthorized,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: 只作为收款 ATA 的 authority, 由 global_config.fee_recipient 约束
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump = bank_account.bump,
+    )]
+    pub bank_account: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY.as_bytes(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = bank_token_account,
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn withdraw_protocol_fees_handler(
+    ctx: Context<WithdrawProtocolFees>,
+    amount: u64,
+) -> Result<()> {
+    let bank_account = &mut ctx.accounts.bank_account;
+    bank_account.accrue_interest(Clock::get()?.unix_timestamp)?;
+    if amount > bank_account.accumulated_protocol_fees {
+        return Err(ErrorCode::ExceedProtocolFees.into());
+    }
+    if amount > ctx.accounts.bank_token_account.amount {
+        return Err(ErrorCode::InsufficientLiquidity.into());
+    }
+    bank_account.accumulated_protocol_fees -= amount;
+
+    let program = ctx.accounts.token_program.to_account_info();
+    let accounts = TransferChecked {
+        from: ctx.accounts.bank_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.fee_recipient_token_account.to_account_info(),
+        authority: ctx.accounts.bank_token_account.to_account_info(),
+    };
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        TREASURY.as_bytes(),
+        mint_key.as_ref(),
+        &[ctx.bumps.bank_token_account],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+    let decimals = ctx.accounts.mint.decimals;
+    transfer_checked(cpi_ctx, amount, decimals)?;
+
+    emit!(WithdrawProtocolFeesEvent {
+        bank: ctx.accounts.bank_account.key(),
+        mint: mint_key,
+        fee_recipient: ctx.accounts.fee_recipient.key(),
+        amount,
+        accumulated_protocol_fees: ctx.accounts.bank_account.accumulated_protocol_fees,
+    });
+
+    Ok(())
+}
@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 pub mod constants;
 pub mod enums;
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod states;
 pub mod utils;
@@ -9,6 +10,7 @@ pub mod utils;
 pub use constants::*;
 pub use enums::*;
 pub use errors::ErrorCode;
+pub use events::*;
 pub use instructions::*;
 pub use states::*;
 pub use utils::*;
@@ -19,24 +21,22 @@ declare_id!("6yT6ZAak1W9iD7mQSvoyHQzXhbdJqktqyFMbhkuAYA2a");
 pub mod lending {
     use super::*;
 
+    pub fn process_init_global_config(
+        ctx: Context<InitGlobalConfig>,
+        admin: Pubkey,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::init_global_config_handler(ctx, admin, fee_recipient)?;
+
+        Ok(())
+    }
+
     pub fn process_init_bank(
         ctx: Context<InitBank>,
-        liquidate_threshold: f64,
-        liquidate_bonus: f64,
-        liquidate_close_factor: f64,
-        max_ltv: f64,
-        deposited_interest_ratio: f64,
-        borrowed_interest_ratio: f64,
+        oracle_config: OracleConfig,
+        bank_config: BankConfig,
     ) -> Result<()> {
-        instructions::init_bank_handler(
-            ctx,
-            liquidate_threshold,
-            liquidate_bonus,
-            liquidate_close_factor,
-            max_ltv,
-            deposited_interest_ratio,
-            borrowed_interest_ratio,
-        )?;
+        instructions::init_bank_handler(ctx, oracle_config, bank_config)?;
 
         Ok(())
     }
@@ -47,60 +47,158 @@ pub mod lending {
         Ok(())
     }
 
-    pub fn process_deposit(ctx: Context<Deposit>, mint: String, amount: u64) -> Result<()> {
-        let mint_kind = match mint.as_str() {
-            SOL => MintKind::SOL,
-            USDC => MintKind::USDC,
-            _ => {
-                return Err(ErrorCode::UnsupportedMintKind.into());
-            }
-        };
-        instructions::deposit_handler(ctx, mint_kind, amount)?;
+    pub fn process_deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_handler(ctx, amount)?;
+
+        Ok(())
+    }
+
+    pub fn process_borrow<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Borrow<'info>>,
+        to_borrow_value: Decimal,
+    ) -> Result<()> {
+        instructions::borrow_handler(ctx, to_borrow_value)?;
+
+        Ok(())
+    }
+
+    pub fn process_repay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Repay<'info>>,
+        to_repay_amount: u64,
+    ) -> Result<()> {
+        instructions::repay_handler(ctx, to_repay_amount)?;
 
         Ok(())
     }
 
-    pub fn process_borrow(
-        ctx: Context<Borrow>,
-        collateralized_kind: String,
-        to_borrow_kind: String,
-        to_borrow_value: f64,
+    pub fn process_withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithDraw<'info>>,
+        amount: u64,
     ) -> Result<()> {
-        let collateralized_kind = match collateralized_kind.as_str() {
-            SOL => MintKind::SOL,
-            USDC => MintKind::USDC,
-            _ => {
-                return Err(ErrorCode::UnsupportedMintKind.into());
-            }
-        };
-        let to_borrow_kind = match to_borrow_kind.as_str() {
-            SOL => MintKind::SOL,
-            USDC => MintKind::USDC,
-            _ => {
-                return Err(ErrorCode::UnsupportedMintKind.into());
-            }
-        };
-        if collateralized_kind == to_borrow_kind {
-            return Err(ErrorCode::BorrowNotAllowed.into());
-        }
-        instructions::borrow_handler(ctx, collateralized_kind, to_borrow_kind, to_borrow_value)?;
-
-        Ok(())
-    }
-
-    pub fn process_repay(
-        ctx: Context<Repay>,
-        repay_kind: String,
+        instructions::withdraw_handler(ctx, amount)?;
+
+        Ok(())
+    }
+
+    pub fn process_liquidate<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Liquidate<'info>>,
         to_repay_amount: u64,
     ) -> Result<()> {
-        let repay_kind = match repay_kind.as_str() {
-            SOL => MintKind::SOL,
-            USDC => MintKind::USDC,
-            _ => {
-                return Err(ErrorCode::UnsupportedMintKind.into());
-            }
-        };
-        instructions::repay_handler(ctx, repay_kind, to_repay_amount)?;
+        instructions::liquidate_handler(ctx, to_repay_amount)?;
+
+        Ok(())
+    }
+
+    pub fn process_set_fee_recipient(
+        ctx: Context<SetFeeRecipient>,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::set_fee_recipient_handler(ctx, fee_recipient)?;
+
+        Ok(())
+    }
+
+    pub fn process_withdraw_protocol_fees(
+        ctx: Context<WithdrawProtocolFees>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_protocol_fees_handler(ctx, amount)?;
+
+        Ok(())
+    }
+
+    pub fn process_lock_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LockCollateral<'info>>,
+        shares: u64,
+    ) -> Result<()> {
+        instructions::lock_collateral_handler(ctx, shares)?;
+
+        Ok(())
+    }
+
+    pub fn process_unlock_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnlockCollateral<'info>>,
+        shares: u64,
+    ) -> Result<()> {
+        instructions::unlock_collateral_handler(ctx, shares)?;
+
+        Ok(())
+    }
+
+    pub fn process_flash_borrow(ctx: Context<FlashBorrow>, amount: u64) -> Result<()> {
+        instructions::flash_borrow_handler(ctx, amount)?;
+
+        Ok(())
+    }
+
+    pub fn process_flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+        instructions::flash_repay_handler(ctx)?;
+
+        Ok(())
+    }
+
+    pub fn process_update_bank_oracle(
+        ctx: Context<UpdateBankOracle>,
+        oracle_config: OracleConfig,
+    ) -> Result<()> {
+        instructions::update_bank_oracle_handler(ctx, oracle_config)?;
+
+        Ok(())
+    }
+
+    pub fn process_update_bank_config(
+        ctx: Context<UpdateBankConfig>,
+        bank_config: BankConfig,
+    ) -> Result<()> {
+        instructions::update_bank_config_handler(ctx, bank_config)?;
+
+        Ok(())
+    }
+
+    pub fn process_propose_bank_authority(
+        ctx: Context<ProposeBankAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_bank_authority_handler(ctx, new_authority)?;
+
+        Ok(())
+    }
+
+    pub fn process_accept_bank_authority(ctx: Context<AcceptBankAuthority>) -> Result<()> {
+        instructions::accept_bank_authority_handler(ctx)?;
+
+        Ok(())
+    }
+
+    pub fn process_propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin_handler(ctx, new_admin)?;
+
+        Ok(())
+    }
+
+    pub fn process_set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        instructions::set_guardian_handler(ctx, guardian)?;
+
+        Ok(())
+    }
+
+    pub fn process_set_pause_flags(ctx: Context<SetPauseFlags>, pause_flags: u64) -> Result<()> {
+        instructions::set_pause_flags_handler(ctx, pause_flags)?;
+
+        Ok(())
+    }
+
+    pub fn process_accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin_handler(ctx)?;
+
+        Ok(())
+    }
+
+    pub fn process_refresh_bank(ctx: Context<RefreshBank>) -> Result<()> {
+        instructions::refresh_bank_handler(ctx)?;
 
         Ok(())
     }
@@ -1,21 +1,266 @@
+use crate::constants::SECONDS_PER_YEAR;
+use crate::decimal::Decimal;
+use crate::enums::Rounding;
+use crate::errors::ErrorCode;
+use crate::events::AccrueInterestEvent;
+use crate::math_utils::{
+    calc_change_shares, calc_linear_growth, calc_new_index, calc_shares_value,
+    calc_virtual_deposit_shares,
+};
+use crate::states::{BankConfig, CachedPrice, InterestRateModel, OracleConfig, OutflowWindow};
 use anchor_lang::prelude::*;
 use derive_builder::Builder;
 
 #[account]
 #[derive(InitSpace, Default, Builder, Debug)]
+#[builder(default)]
 pub struct Bank {
     pub authority: Pubkey,
+    ///待接受的新 authority, 无转移时为 Pubkey::default()
+    pub pending_authority: Pubkey,
     pub mint: Pubkey,
+    pub mint_decimals: u8,
+    ///存款凭证 mint, 总供应量等于 total_deposited_shares
+    pub receipt_mint: Pubkey,
+    pub oracle_config: OracleConfig,
+    pub cached_price: CachedPrice,
     pub total_deposited_amount: u64,
-    pub total_deposited_shares: f64,
+    pub total_deposited_shares: u64,
     pub total_borrowed_amount: u64,
-    pub total_borrowed_shares: f64,
-    pub liquidate_threshold: f64,
-    pub liquidate_bonus: f64,
-    pub liquidate_close_factor: f64,
-    pub max_ltv: f64,
-    pub deposited_interest_ratio: f64,
-    pub borrowed_interest_ratio: f64,
+    pub total_borrowed_shares: u64,
+    pub liquidate_threshold: Decimal,
+    pub liquidate_bonus: Decimal,
+    pub liquidate_close_factor: Decimal,
+    pub max_ltv: Decimal,
+    pub interest_rate_model: InterestRateModel,
+    pub reserve_factor: Decimal,
+    ///总存款上限, 0 表示不限制
+    pub deposit_cap: u64,
+    ///总借款上限, 0 表示不限制
+    pub borrow_cap: u64,
+    ///闪电贷手续费率
+    pub flash_loan_fee: Decimal,
+    ///出金限流的滑动窗口长度(秒), 0 表示不限流
+    pub outflow_window: i64,
+    ///窗口内最大净流出数量, 0 表示不限制
+    pub max_outflow_amount: u64,
+    ///窗口内最大净流出占当前流动性的比例, 0 表示不限制
+    pub max_outflow_ratio: Decimal,
+    ///withdraw, borrow 及清算的净流出记录
+    pub outflow: OutflowWindow,
+    pub supply_index: Decimal,
+    pub borrow_index: Decimal,
+    ///按 reserve_factor 从借款利息中累积的协议收入
+    pub accumulated_protocol_fees: u64,
+    ///进行中的闪电贷本金, 无闪电贷时为 0
+    pub flash_loan_amount: u64,
+    ///暂停标志位
+    pub pause_flags: u64,
     pub bump: u8,
     pub last_updated: i64,
 }
+
+impl Bank {
+    pub fn set_config(&mut self, bank_config: &BankConfig) {
+        self.liquidate_threshold = bank_config.liquidate_threshold;
+        self.liquidate_bonus = bank_config.liquidate_bonus;
+        self.liquidate_close_factor = bank_config.liquidate_close_factor;
+        self.max_ltv = bank_config.max_ltv;
+        self.interest_rate_model = bank_config.interest_rate_model;
+        self.reserve_factor = bank_config.reserve_factor;
+        self.deposit_cap = bank_config.deposit_cap;
+        self.borrow_cap = bank_config.borrow_cap;
+        self.flash_loan_fee = bank_config.flash_loan_fee;
+        self.outflow_window = bank_config.outflow_window;
+        self.max_outflow_amount = bank_config.max_outflow_amount;
+        self.max_outflow_ratio = bank_config.max_outflow_ratio;
+    }
+
+    ///窗口内允许的最大净流出, 取数量上限和流动性比例上限中较小者, 均未配置时为 None
+    fn outflow_limit(&self, liquidity: u64) -> Result<Option<u64>> {
+        let amount_limit = (self.max_outflow_amount > 0).then_some(self.max_outflow_amount);
+        let ratio_limit = if self.max_outflow_ratio > Decimal::ZERO {
+            Some(
+                self.max_outflow_ratio
+                    .try_mul_u64(liquidity)?
+                    .try_to_u64(Rounding::Down)?,
+            )
+        } else {
+            None
+        };
+
+        Ok(match (amount_limit, ratio_limit) {
+            (Some(amount_limit), Some(ratio_limit)) => Some(amount_limit.min(ratio_limit)),
+            (amount_limit, ratio_limit) => amount_limit.or(ratio_limit),
+        })
+    }
+
+    ///记录从 treasury 流出的 amount, liquidity 为流出前的 treasury 余额, 超过窗口限额时拒绝
+    pub fn record_outflow(&mut self, amount: u64, liquidity: u64, current_time: i64) -> Result<()> {
+        if self.outflow_window <= 0 {
+            return Ok(());
+        }
+        let limit = self.outflow_limit(liquidity)?;
+        self.outflow.roll(self.outflow_window, current_time);
+        let outflow = self
+            .outflow
+            .rolling_outflow(self.outflow_window, current_time)?
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if limit.is_some_and(|limit| outflow > limit) {
+            return Err(ErrorCode::OutflowLimitExceeded.into());
+        }
+        self.outflow.current_outflow = self
+            .outflow
+            .current_outflow
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    ///记录流入 treasury 的 amount, 抵扣当前窗口的净流出
+    pub fn record_inflow(&mut self, amount: u64, current_time: i64) {
+        if self.outflow_window <= 0 {
+            return;
+        }
+        self.outflow.roll(self.outflow_window, current_time);
+        self.outflow.current_outflow = self.outflow.current_outflow.saturating_sub(amount);
+    }
+
+    ///init_bank 时铸造到 virtual_deposit_vault 的份额, 不属于任何用户
+    pub fn virtual_deposit_shares(&self) -> Result<u64> {
+        calc_virtual_deposit_shares(self.mint_decimals)
+    }
+
+    ///存入 amount 并返回铸造给用户的份额, 份额向下取整
+    ///
+    ///虚拟存款不计入 deposit_cap
+    pub fn add_deposit(&mut self, amount: u64) -> Result<u64> {
+        let new_total_deposited_amount = self
+            .total_deposited_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.deposit_cap > 0 {
+            let virtual_deposit_amount = calc_shares_value(
+                self.virtual_deposit_shares()?,
+                self.supply_index,
+                Rounding::Down,
+            )?;
+            if new_total_deposited_amount.saturating_sub(virtual_deposit_amount) > self.deposit_cap
+            {
+                return Err(ErrorCode::DepositCapExceeded.into());
+            }
+        }
+        let shares = calc_change_shares(amount, self.supply_index, Rounding::Down)?;
+        require!(shares > 0, ErrorCode::DepositTooSmall);
+        let new_total_deposited_shares = self
+            .total_deposited_shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.total_deposited_amount = new_total_deposited_amount;
+        self.total_deposited_shares = new_total_deposited_shares;
+
+        Ok(shares)
+    }
+
+    ///从持有 user_shares 份额的用户存款中取出 amount, 返回扣除的份额
+    ///
+    ///份额向上取整, 取出全部余额时扣除全部份额, 不留下无法取出的零头
+    pub fn remove_deposit(&mut self, amount: u64, user_shares: u64) -> Result<u64> {
+        require!(user_shares > 0, ErrorCode::NotEnoughBalance);
+        let user_deposited_amount =
+            calc_shares_value(user_shares, self.supply_index, Rounding::Down)?;
+        require!(amount <= user_deposited_amount, ErrorCode::NotEnoughBalance);
+        let shares = if amount == user_deposited_amount {
+            user_shares
+        } else {
+            calc_change_shares(amount, self.supply_index, Rounding::Up)?.min(user_shares)
+        };
+
+        self.total_deposited_amount = self.total_deposited_amount.saturating_sub(amount);
+        self.total_deposited_shares = self.total_deposited_shares.saturating_sub(shares);
+
+        Ok(shares)
+    }
+
+    ///资金利用率 = 总借款 / 总存款
+    pub fn utilization(&self) -> Result<Decimal> {
+        if self.total_deposited_amount == 0 {
+            return Ok(Decimal::ZERO);
+        }
+        let utilization = Decimal::from_ratio(
+            self.total_borrowed_amount,
+            self.total_deposited_amount,
+            Rounding::Down,
+        )?;
+
+        Ok(utilization.min(Decimal::ONE))
+    }
+
+    ///结算利息并发出 AccrueInterestEvent
+    pub fn accrue_interest(&mut self, current_time: i64) -> Result<()> {
+        if let Some(event) = self.update_interest(current_time)? {
+            emit!(event);
+        }
+
+        Ok(())
+    }
+
+    ///累积自 last_updated 以来的利息到存借款指数, 并刷新总额, 不发出事件
+    ///
+    ///本次借款利息 (total_borrowed_shares * 借款指数增量) 中 reserve_factor 部分计入 accumulated_protocol_fees
+    pub fn update_interest(&mut self, current_time: i64) -> Result<Option<AccrueInterestEvent>> {
+        let elapsed = current_time - self.last_updated;
+        if elapsed <= 0 {
+            return Ok(None);
+        }
+
+        let utilization = self.utilization()?;
+        let previous_borrow_index = self.borrow_index;
+        let borrow_rate = self
+            .interest_rate_model
+            .borrow_rate(utilization)?
+            .try_div_u64(SECONDS_PER_YEAR, Rounding::Up)?;
+        let supply_rate = self
+            .interest_rate_model
+            .supply_rate(utilization, self.reserve_factor)?
+            .try_div_u64(SECONDS_PER_YEAR, Rounding::Down)?;
+
+        self.borrow_index = calc_new_index(self.borrow_index, borrow_rate, elapsed)?;
+        self.supply_index = self
+            .supply_index
+            .try_mul(calc_linear_growth(supply_rate, elapsed)?, Rounding::Down)?;
+        self.total_deposited_amount = calc_shares_value(
+            self.total_deposited_shares,
+            self.supply_index,
+            Rounding::Down,
+        )?;
+        self.total_borrowed_amount =
+            calc_shares_value(self.total_borrowed_shares, self.borrow_index, Rounding::Up)?;
+        let protocol_fees = Decimal::from_u64(self.total_borrowed_shares)
+            .try_mul(
+                self.borrow_index.try_sub(previous_borrow_index)?,
+                Rounding::Down,
+            )?
+            .try_mul(self.reserve_factor, Rounding::Down)?
+            .try_to_u64(Rounding::Down)?;
+        self.accumulated_protocol_fees = self
+            .accumulated_protocol_fees
+            .checked_add(protocol_fees)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.last_updated = current_time;
+
+        Ok(Some(AccrueInterestEvent {
+            mint: self.mint,
+            elapsed,
+            utilization,
+            supply_index: self.supply_index,
+            borrow_index: self.borrow_index,
+            total_deposited_amount: self.total_deposited_amount,
+            total_borrowed_amount: self.total_borrowed_amount,
+            protocol_fees,
+        }))
+    }
+}
@@ -0,0 +1,26 @@
+use crate::decimal::Decimal;
+use crate::states::InterestRateModel;
+use anchor_lang::prelude::*;
+
+///Bank 的风险参数及利率配置
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub struct BankConfig {
+    pub liquidate_threshold: Decimal,
+    pub liquidate_bonus: Decimal,
+    pub liquidate_close_factor: Decimal,
+    pub max_ltv: Decimal,
+    pub interest_rate_model: InterestRateModel,
+    pub reserve_factor: Decimal,
+    ///总存款上限, 0 表示不限制
+    pub deposit_cap: u64,
+    ///总借款上限, 0 表示不限制
+    pub borrow_cap: u64,
+    ///闪电贷手续费率
+    pub flash_loan_fee: Decimal,
+    ///出金限流的滑动窗口长度(秒), 0 表示不限流
+    pub outflow_window: i64,
+    ///窗口内最大净流出数量, 0 表示不限制
+    pub max_outflow_amount: u64,
+    ///窗口内最大净流出占当前流动性的比例, 0 表示不限制
+    pub max_outflow_ratio: Decimal,
+}
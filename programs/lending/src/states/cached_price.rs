@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+///refresh_bank 缓存的最近一次校验通过的预言机价格
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub struct CachedPrice {
+    pub price: i64,
+    pub exponent: i32,
+    ///价格发布时间, 未刷新时为 0
+    pub publish_time: i64,
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use derive_builder::Builder;
+
+///协议全局配置, 全局唯一
+#[account]
+#[derive(InitSpace, Default, Debug, Builder)]
+#[builder(default)]
+pub struct GlobalConfig {
+    ///协议管理员, 只有管理员可以创建 Bank
+    pub admin: Pubkey,
+    ///待接受的新管理员, 无转移时为 Pubkey::default()
+    pub pending_admin: Pubkey,
+    ///协议费用接收地址
+    pub fee_recipient: Pubkey,
+    ///guardian 只能暂停, 不能恢复
+    pub guardian: Pubkey,
+    ///全局暂停标志位
+    pub pause_flags: u64,
+    pub bump: u8,
+}
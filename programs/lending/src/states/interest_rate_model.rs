@@ -0,0 +1,41 @@
+use crate::decimal::Decimal;
+use crate::enums::Rounding;
+use anchor_lang::prelude::*;
+
+///分段(拐点)利率模型, 所有利率均为年化
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub struct InterestRateModel {
+    pub base_rate: Decimal,
+    pub slope1: Decimal,
+    pub optimal_utilization: Decimal,
+    pub slope2: Decimal,
+}
+
+impl InterestRateModel {
+    ///借款年化利率
+    pub fn borrow_rate(&self, utilization: Decimal) -> Result<Decimal> {
+        if utilization <= self.optimal_utilization {
+            if self.optimal_utilization == Decimal::ZERO {
+                return Ok(self.base_rate);
+            }
+            let ratio = utilization.try_div(self.optimal_utilization, Rounding::Up)?;
+            return self
+                .base_rate
+                .try_add(self.slope1.try_mul(ratio, Rounding::Up)?);
+        }
+
+        let excess_utilization = utilization.try_sub(self.optimal_utilization)?;
+        let max_excess_utilization = Decimal::ONE.try_sub(self.optimal_utilization)?;
+        let ratio = excess_utilization.try_div(max_excess_utilization, Rounding::Up)?;
+        self.base_rate
+            .try_add(self.slope1)?
+            .try_add(self.slope2.try_mul(ratio, Rounding::Up)?)
+    }
+
+    ///存款年化利率 = 借款利率 * 利用率 * (1 - 储备金率)
+    pub fn supply_rate(&self, utilization: Decimal, reserve_factor: Decimal) -> Result<Decimal> {
+        self.borrow_rate(utilization)?
+            .try_mul(utilization, Rounding::Down)?
+            .try_mul(Decimal::ONE.try_sub(reserve_factor)?, Rounding::Down)
+    }
+}
@@ -1,5 +1,19 @@
 mod bank;
+mod bank_config;
+mod cached_price;
+mod global_config;
+mod interest_rate_model;
+mod oracle_config;
+mod outflow_window;
+mod position;
 mod user;
 
 pub use bank::*;
+pub use bank_config::*;
+pub use cached_price::*;
+pub use global_config::*;
+pub use interest_rate_model::*;
+pub use oracle_config::*;
+pub use outflow_window::*;
+pub use position::*;
 pub use user::*;
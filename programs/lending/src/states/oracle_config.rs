@@ -0,0 +1,11 @@
+use crate::enums::OracleType;
+use anchor_lang::prelude::*;
+
+///Bank 的预言机配置
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub struct OracleConfig {
+    pub oracle_type: OracleType,
+    pub feed_id: [u8; 32],
+    ///价格最大允许延迟(秒)
+    pub max_age: u64,
+}
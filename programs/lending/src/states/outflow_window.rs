@@ -0,0 +1,46 @@
+use crate::decimal::mul_div;
+use crate::enums::Rounding;
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+///Bank 的净流出滑动窗口
+///
+///最近一个窗口长度内的净流出按 当前窗口 + 上一窗口 * 上一窗口仍在范围内的时间占比 估算
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub struct OutflowWindow {
+    pub window_start: i64,
+    ///当前窗口的净流出
+    pub current_outflow: u64,
+    ///上一窗口的净流出
+    pub previous_outflow: u64,
+}
+
+impl OutflowWindow {
+    ///滚动到 current_time 所在的窗口
+    pub fn roll(&mut self, window: i64, current_time: i64) {
+        let elapsed_windows = (current_time - self.window_start).max(0) / window;
+        match elapsed_windows {
+            0 => return,
+            1 => self.previous_outflow = self.current_outflow,
+            _ => self.previous_outflow = 0,
+        }
+        self.current_outflow = 0;
+        self.window_start = current_time - (current_time - self.window_start) % window;
+    }
+
+    ///最近一个窗口长度内的净流出, 需先调用 roll
+    pub fn rolling_outflow(&self, window: i64, current_time: i64) -> Result<u64> {
+        let remaining = (window - (current_time - self.window_start)).clamp(0, window);
+        let previous_outflow = mul_div(
+            self.previous_outflow as u128,
+            remaining as u128,
+            window as u128,
+            Rounding::Up,
+        )?;
+
+        u64::try_from(previous_outflow)
+            .ok()
+            .and_then(|previous_outflow| previous_outflow.checked_add(self.current_outflow))
+            .ok_or_else(|| ErrorCode::MathOverflow.into())
+    }
+}
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+///用户在单个 Bank 中的存借款份额
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq)]
+pub struct Position {
+    pub bank: Pubkey,
+    pub deposited_shares: u64,
+    pub borrowed_shares: u64,
+}
+
+impl Position {
+    pub fn is_empty(&self) -> bool {
+        self.deposited_shares == 0 && self.borrowed_shares == 0
+    }
+}
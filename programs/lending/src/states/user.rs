@@ -1,21 +1,66 @@
+use crate::constants::MAX_POSITIONS;
+use crate::decimal::Decimal;
+use crate::errors::ErrorCode;
+use crate::states::Position;
 use anchor_lang::prelude::*;
 use derive_builder::Builder;
 
 #[account]
 #[derive(InitSpace, Default, Debug, Builder)]
+#[builder(default)]
 pub struct User {
     pub owner: Pubkey,
-    pub deposited_usdc_amount: u64,
-    pub deposited_usdc_shares: f64,
-    pub borrowed_usdc_amount: u64,
-    pub borrowed_usdc_shares: f64,
-    pub deposited_sol_amount: u64,
-    pub deposited_sol_shares: f64,
-    pub borrowed_sol_amount: u64,
-    pub borrowed_sol_shares: f64,
-    pub deposited_mint: Pubkey,
-    pub borrowed_mint: Pubkey,
-    pub health_factor: f64,
+    #[max_len(MAX_POSITIONS)]
+    pub positions: Vec<Position>,
+    pub health_factor: Decimal,
     pub bump: u8,
     pub last_updated: i64,
 }
+
+impl User {
+    pub fn find_position(&self, bank: &Pubkey) -> Option<&Position> {
+        self.positions
+            .iter()
+            .find(|position| position.bank == *bank)
+    }
+
+    pub fn find_position_mut(&mut self, bank: &Pubkey) -> Option<&mut Position> {
+        self.positions
+            .iter_mut()
+            .find(|position| position.bank == *bank)
+    }
+
+    ///查找仓位, 不存在时新建
+    pub fn find_or_add_position(&mut self, bank: &Pubkey) -> Result<&mut Position> {
+        let index = match self
+            .positions
+            .iter()
+            .position(|position| position.bank == *bank)
+        {
+            Some(index) => index,
+            None => {
+                require!(
+                    self.positions.len() < MAX_POSITIONS,
+                    ErrorCode::TooManyPositions
+                );
+                self.positions.push(Position {
+                    bank: *bank,
+                    ..Default::default()
+                });
+                self.positions.len() - 1
+            }
+        };
+
+        Ok(&mut self.positions[index])
+    }
+
+    pub fn remove_empty_positions(&mut self) {
+        self.positions.retain(|position| !position.is_empty());
+    }
+
+    pub fn has_borrowed(&self) -> bool {
+        self.positions
+            .iter()
+            .any(|position| position.borrowed_shares > 0)
+    }
+}
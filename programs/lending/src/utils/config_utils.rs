@@ -0,0 +1,44 @@
+use crate::decimal::Decimal;
+use crate::errors::ErrorCode;
+use crate::states::BankConfig;
+use anchor_lang::prelude::*;
+
+///校验 Bank 配置
+///
+///0 <= max_ltv < liquidate_threshold <= 1, liquidate_bonus < 1 - liquidate_threshold,
+///0 < liquidate_close_factor <= 1, reserve_factor <= 1, optimal_utilization <= 1,
+///flash_loan_fee <= 1, outflow_window >= 0, max_outflow_ratio <= 1
+pub fn validate_bank_config(bank_config: &BankConfig) -> Result<()> {
+    require!(
+        bank_config.max_ltv < bank_config.liquidate_threshold
+            && bank_config.liquidate_threshold <= Decimal::ONE,
+        ErrorCode::InvalidBankConfig
+    );
+    require!(
+        bank_config.liquidate_bonus < Decimal::ONE.try_sub(bank_config.liquidate_threshold)?,
+        ErrorCode::InvalidBankConfig
+    );
+    require!(
+        bank_config.liquidate_close_factor > Decimal::ZERO
+            && bank_config.liquidate_close_factor <= Decimal::ONE,
+        ErrorCode::InvalidBankConfig
+    );
+    require!(
+        bank_config.reserve_factor <= Decimal::ONE,
+        ErrorCode::InvalidBankConfig
+    );
+    require!(
+        bank_config.interest_rate_model.optimal_utilization <= Decimal::ONE,
+        ErrorCode::InvalidBankConfig
+    );
+    require!(
+        bank_config.flash_loan_fee <= Decimal::ONE,
+        ErrorCode::InvalidBankConfig
+    );
+    require!(
+        bank_config.outflow_window >= 0 && bank_config.max_outflow_ratio <= Decimal::ONE,
+        ErrorCode::InvalidBankConfig
+    );
+
+    Ok(())
+}
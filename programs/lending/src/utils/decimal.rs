@@ -0,0 +1,126 @@
+use crate::enums::Rounding;
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+use std::fmt;
+
+#[allow(
+    clippy::manual_div_ceil,
+    clippy::assign_op_pattern,
+    clippy::ptr_offset_with_cast
+)]
+mod u256 {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+use u256::U256;
+
+///定点数精度 1e18
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+///万分比基数
+pub const BPS: u128 = 10_000;
+
+///以 WAD(1e18) 为精度的无符号定点数
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct Decimal(pub u128);
+
+impl Space for Decimal {
+    const INIT_SPACE: usize = 16;
+}
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal(0);
+    pub const ONE: Decimal = Decimal(WAD);
+    pub const MAX: Decimal = Decimal(u128::MAX);
+
+    pub fn from_u64(value: u64) -> Self {
+        Decimal(value as u128 * WAD)
+    }
+
+    pub fn from_bps(bps: u64) -> Self {
+        Decimal(bps as u128 * WAD / BPS)
+    }
+
+    ///numerator / denominator
+    pub fn from_ratio(numerator: u64, denominator: u64, rounding: Rounding) -> Result<Self> {
+        mul_div(numerator as u128, WAD, denominator as u128, rounding).map(Decimal)
+    }
+
+    pub fn try_add(self, rhs: Decimal) -> Result<Self> {
+        self.0
+            .checked_add(rhs.0)
+            .map(Decimal)
+            .ok_or_else(|| ErrorCode::MathOverflow.into())
+    }
+
+    pub fn try_sub(self, rhs: Decimal) -> Result<Self> {
+        self.0
+            .checked_sub(rhs.0)
+            .map(Decimal)
+            .ok_or_else(|| ErrorCode::MathOverflow.into())
+    }
+
+    pub fn try_mul(self, rhs: Decimal, rounding: Rounding) -> Result<Self> {
+        mul_div(self.0, rhs.0, WAD, rounding).map(Decimal)
+    }
+
+    pub fn try_div(self, rhs: Decimal, rounding: Rounding) -> Result<Self> {
+        mul_div(self.0, WAD, rhs.0, rounding).map(Decimal)
+    }
+
+    pub fn try_mul_u64(self, rhs: u64) -> Result<Self> {
+        self.0
+            .checked_mul(rhs as u128)
+            .map(Decimal)
+            .ok_or_else(|| ErrorCode::MathOverflow.into())
+    }
+
+    pub fn try_div_u64(self, rhs: u64, rounding: Rounding) -> Result<Self> {
+        mul_div(self.0, 1, rhs as u128, rounding).map(Decimal)
+    }
+
+    ///转换为整数, 按 rounding 取整
+    pub fn try_to_u64(self, rounding: Rounding) -> Result<u64> {
+        let value = mul_div(self.0, 1, WAD, rounding)?;
+        u64::try_from(value).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
+
+impl fmt::Debug for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:018}", self.0 / WAD, self.0 % WAD)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+///a * b / c, 中间结果使用 256 位避免溢出
+pub fn mul_div(a: u128, b: u128, c: u128, rounding: Rounding) -> Result<u128> {
+    if c == 0 {
+        return Err(ErrorCode::DivideByZero.into());
+    }
+    let product = U256::from(a) * U256::from(b);
+    let divisor = U256::from(c);
+    let mut quotient = product / divisor;
+    if rounding == Rounding::Up && !(product % divisor).is_zero() {
+        quotient += U256::one();
+    }
+    if quotient > U256::from(u128::MAX) {
+        return Err(ErrorCode::MathOverflow.into());
+    }
+    Ok(quotient.as_u128())
+}
+
+///10 的 exp 次方
+pub fn pow10(exp: u32) -> Result<u128> {
+    10u128
+        .checked_pow(exp)
+        .ok_or_else(|| ErrorCode::MathOverflow.into())
+}
@@ -0,0 +1,144 @@
+use crate::decimal::Decimal;
+use crate::enums::Rounding;
+use crate::errors::ErrorCode;
+use crate::math_utils::{calc_shares_value, calc_token_value};
+use crate::oracle_utils::{get_bank_price, get_cached_bank_price};
+use crate::states::{Bank, Position, User};
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+///指令中已作为具名账户传入的 Bank 及其价格账户
+pub struct BankPrice<'a> {
+    pub key: Pubkey,
+    pub bank: &'a Bank,
+    pub price_update: &'a PriceUpdateV2,
+}
+
+///用户所有仓位按美元计的汇总价值
+#[derive(Default, Debug)]
+pub struct PositionsValue {
+    ///按各 Bank max_ltv 加权的存款价值
+    pub ltv_weighted_deposited_value: Decimal,
+    ///按各 Bank liquidate_threshold 加权的存款价值
+    pub threshold_weighted_deposited_value: Decimal,
+    pub borrowed_value: Decimal,
+}
+
+impl PositionsValue {
+    ///健康因子 = 按清算阈值加权的存款价值 / 借款价值, 无借款时为 Decimal::MAX
+    pub fn health_factor(&self) -> Result<Decimal> {
+        if self.borrowed_value == Decimal::ZERO {
+            return Ok(Decimal::MAX);
+        }
+
+        self.threshold_weighted_deposited_value
+            .try_div(self.borrowed_value, Rounding::Down)
+    }
+
+    ///借款价值不超过按 max_ltv 加权的存款价值
+    pub fn is_within_ltv(&self) -> bool {
+        self.borrowed_value <= self.ltv_weighted_deposited_value
+    }
+
+    pub fn is_liquidatable(&self) -> Result<bool> {
+        Ok(self.health_factor()? < Decimal::ONE)
+    }
+
+    ///按给定价格累加单个仓位的价值, bank 需已结算利息
+    pub fn add_position(
+        &mut self,
+        position: &Position,
+        bank: &Bank,
+        price: i64,
+        exponent: i32,
+    ) -> Result<()> {
+        let deposited_amount =
+            calc_shares_value(position.deposited_shares, bank.supply_index, Rounding::Down)?;
+        let deposited_value = calc_token_value(
+            deposited_amount,
+            bank.mint_decimals,
+            price,
+            exponent,
+            Rounding::Down,
+        )?;
+        let borrowed_amount =
+            calc_shares_value(position.borrowed_shares, bank.borrow_index, Rounding::Up)?;
+        let borrowed_value = calc_token_value(
+            borrowed_amount,
+            bank.mint_decimals,
+            price,
+            exponent,
+            Rounding::Up,
+        )?;
+
+        self.ltv_weighted_deposited_value = self
+            .ltv_weighted_deposited_value
+            .try_add(deposited_value.try_mul(bank.max_ltv, Rounding::Down)?)?;
+        self.threshold_weighted_deposited_value = self
+            .threshold_weighted_deposited_value
+            .try_add(deposited_value.try_mul(bank.liquidate_threshold, Rounding::Down)?)?;
+        self.borrowed_value = self.borrowed_value.try_add(borrowed_value)?;
+
+        Ok(())
+    }
+}
+
+///重新计算用户的健康因子, 无借款时为 Decimal::MAX, 不读取价格
+pub fn calc_health_factor<'info>(
+    user: &User,
+    named_banks: &[BankPrice],
+    remaining_accounts: &'info [AccountInfo<'info>],
+    clock: &Clock,
+) -> Result<Decimal> {
+    if !user.has_borrowed() {
+        return Ok(Decimal::MAX);
+    }
+
+    calc_positions_value(user, named_banks, remaining_accounts, clock)?.health_factor()
+}
+
+///汇总用户所有仓位的价值
+///
+///具名 Bank 直接使用 named_banks 中的内存状态和价格账户, 其余仓位需在 remaining_accounts 中
+///按仓位顺序依次传入 bank_account, 使用 refresh_bank 缓存在 Bank 上的价格
+pub fn calc_positions_value<'info>(
+    user: &User,
+    named_banks: &[BankPrice],
+    remaining_accounts: &'info [AccountInfo<'info>],
+    clock: &Clock,
+) -> Result<PositionsValue> {
+    let mut remaining_accounts = remaining_accounts.iter();
+    let mut positions_value = PositionsValue::default();
+
+    for position in user.positions.iter() {
+        let loaded_bank;
+        let (bank, price, exponent) = match named_banks
+            .iter()
+            .find(|named_bank| named_bank.key == position.bank)
+        {
+            Some(named_bank) => {
+                let price = get_bank_price(named_bank.bank, named_bank.price_update, clock)?;
+                (named_bank.bank, price.price, price.exponent)
+            }
+            None => {
+                let Some(bank_info) = remaining_accounts.next() else {
+                    return Err(ErrorCode::InvalidRemainingAccounts.into());
+                };
+                require_keys_eq!(
+                    bank_info.key(),
+                    position.bank,
+                    ErrorCode::InvalidRemainingAccounts
+                );
+                let mut bank = Account::<Bank>::try_from(bank_info)?.into_inner();
+                //只读加载, 不会写回账户, 因此不发出事件
+                bank.update_interest(clock.unix_timestamp)?;
+                let cached_price = get_cached_bank_price(&bank, clock)?;
+                loaded_bank = bank;
+                (&loaded_bank, cached_price.price, cached_price.exponent)
+            }
+        };
+        positions_value.add_position(position, bank, price, exponent)?;
+    }
+
+    Ok(positions_value)
+}
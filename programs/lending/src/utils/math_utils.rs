@@ -0,0 +1,99 @@
+use crate::constants::VIRTUAL_DEPOSIT_DECIMALS;
+use crate::decimal::{mul_div, pow10, Decimal, WAD};
+use crate::enums::Rounding;
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+///虚拟存款份额, 按 mint 精度折合 10^-VIRTUAL_DEPOSIT_DECIMALS 个代币, 至少为 1
+pub fn calc_virtual_deposit_shares(decimals: u8) -> Result<u64> {
+    let shares = pow10(decimals.saturating_sub(VIRTUAL_DEPOSIT_DECIMALS) as u32)?;
+
+    u64::try_from(shares).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+///计算本次改变的份额 (金额 / 指数)
+pub fn calc_change_shares(
+    current_change_value: u64,
+    index: Decimal,
+    rounding: Rounding,
+) -> Result<u64> {
+    Decimal::from_u64(current_change_value)
+        .try_div(index, rounding)?
+        .try_to_u64(rounding)
+}
+
+///计算份额对应的金额 (份额 * 指数)
+pub fn calc_shares_value(shares: u64, index: Decimal, rounding: Rounding) -> Result<u64> {
+    Decimal::from_u64(shares)
+        .try_mul(index, rounding)?
+        .try_to_u64(rounding)
+}
+
+///复利增长因子 e^(rt), 取三阶泰勒展开
+pub fn calc_compound_growth(interest: Decimal, elapsed: i64) -> Result<Decimal> {
+    let elapsed = u64::try_from(elapsed).unwrap_or_default();
+    let rt = interest.try_mul_u64(elapsed)?;
+    let rt_2 = rt.try_mul(rt, Rounding::Down)?;
+    let rt_3 = rt_2.try_mul(rt, Rounding::Down)?;
+
+    Decimal::ONE
+        .try_add(rt)?
+        .try_add(rt_2.try_div_u64(2, Rounding::Down)?)?
+        .try_add(rt_3.try_div_u64(6, Rounding::Down)?)
+}
+
+///单利增长因子 1 + rt
+pub fn calc_linear_growth(interest: Decimal, elapsed: i64) -> Result<Decimal> {
+    let elapsed = u64::try_from(elapsed).unwrap_or_default();
+
+    Decimal::ONE.try_add(interest.try_mul_u64(elapsed)?)
+}
+
+///按利率和经过时间计算新的累积利息指数
+pub fn calc_new_index(index: Decimal, interest: Decimal, elapsed: i64) -> Result<Decimal> {
+    index.try_mul(calc_compound_growth(interest, elapsed)?, Rounding::Down)
+}
+
+///预言机价格 price * 10^exponent 转换为定点数
+pub fn calc_price_decimal(price: i64, exponent: i32) -> Result<Decimal> {
+    let price = u64::try_from(price).map_err(|_| ErrorCode::InvalidPrice)?;
+    require!(price > 0, ErrorCode::InvalidPrice);
+
+    let scale = pow10(exponent.unsigned_abs())?;
+    if exponent < 0 {
+        mul_div(price as u128, WAD, scale, Rounding::Down).map(Decimal)
+    } else {
+        Decimal::from_u64(price)
+            .0
+            .checked_mul(scale)
+            .map(Decimal)
+            .ok_or_else(|| ErrorCode::MathOverflow.into())
+    }
+}
+
+///代币数量(最小单位)按价格换算为美元价值
+pub fn calc_token_value(
+    amount: u64,
+    decimals: u8,
+    price: i64,
+    exponent: i32,
+    rounding: Rounding,
+) -> Result<Decimal> {
+    let price = calc_price_decimal(price, exponent)?;
+
+    mul_div(amount as u128, price.0, pow10(decimals as u32)?, rounding).map(Decimal)
+}
+
+///美元价值按价格换算为代币数量(最小单位)
+pub fn calc_token_amount(
+    value: Decimal,
+    decimals: u8,
+    price: i64,
+    exponent: i32,
+    rounding: Rounding,
+) -> Result<u64> {
+    let price = calc_price_decimal(price, exponent)?;
+    let amount = mul_div(value.0, pow10(decimals as u32)?, price.0, rounding)?;
+
+    u64::try_from(amount).map_err(|_| ErrorCode::MathOverflow.into())
+}
@@ -1,2 +1,12 @@
-pub mod float_utils;
-pub use float_utils::*;
\ No newline at end of file
+pub mod config_utils;
+pub mod decimal;
+pub mod health_utils;
+pub mod math_utils;
+pub mod oracle_utils;
+pub mod pause_utils;
+pub use config_utils::*;
+pub use decimal::*;
+pub use health_utils::*;
+pub use math_utils::*;
+pub use oracle_utils::*;
+pub use pause_utils::*;
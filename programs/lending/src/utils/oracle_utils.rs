@@ -0,0 +1,48 @@
+use crate::enums::OracleType;
+use crate::errors::ErrorCode;
+use crate::states::{Bank, CachedPrice, OracleConfig};
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{Price, PriceUpdateV2};
+
+///校验预言机配置
+pub fn validate_oracle_config(oracle_config: &OracleConfig) -> Result<()> {
+    require!(
+        oracle_config.feed_id != [0u8; 32] && oracle_config.max_age > 0,
+        ErrorCode::InvalidOracleConfig
+    );
+
+    Ok(())
+}
+
+///按 Bank 的预言机配置读取价格, 价格账户必须与配置的 feed 对应
+pub fn get_bank_price(bank: &Bank, price_update: &PriceUpdateV2, clock: &Clock) -> Result<Price> {
+    let oracle_config = &bank.oracle_config;
+    match oracle_config.oracle_type {
+        OracleType::PythPull => {
+            require!(
+                price_update.price_message.feed_id == oracle_config.feed_id,
+                ErrorCode::InvalidOracleAccount
+            );
+            let price = price_update.get_price_no_older_than(
+                clock,
+                oracle_config.max_age,
+                &oracle_config.feed_id,
+            )?;
+
+            Ok(price)
+        }
+    }
+}
+
+///读取 refresh_bank 缓存的价格, 缓存不得早于预言机配置的 max_age
+pub fn get_cached_bank_price(bank: &Bank, clock: &Clock) -> Result<CachedPrice> {
+    let cached_price = bank.cached_price;
+    let max_age = i64::try_from(bank.oracle_config.max_age).unwrap_or(i64::MAX);
+    require!(
+        cached_price.price > 0
+            && cached_price.publish_time.saturating_add(max_age) >= clock.unix_timestamp,
+        ErrorCode::StalePriceCache
+    );
+
+    Ok(cached_price)
+}
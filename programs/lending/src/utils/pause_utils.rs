@@ -0,0 +1,25 @@
+use crate::constants::PAUSE_ALL;
+use crate::errors::ErrorCode;
+use crate::states::{Bank, GlobalConfig};
+use anchor_lang::prelude::*;
+
+///校验操作未被全局或 Bank 暂停
+pub fn require_not_paused(global_config: &GlobalConfig, bank: &Bank, flag: u64) -> Result<()> {
+    require!(
+        (global_config.pause_flags | bank.pause_flags) & flag == 0,
+        ErrorCode::OperationPaused
+    );
+
+    Ok(())
+}
+
+///校验新的暂停标志位, guardian 只能新增标志位(暂停), 不能清除(恢复)
+pub fn validate_pause_flags(current_flags: u64, new_flags: u64, is_authority: bool) -> Result<()> {
+    require!(new_flags & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+    require!(
+        is_authority || new_flags & current_flags == current_flags,
+        ErrorCode::Unauthorized
+    );
+
+    Ok(())
+}
@@ -0,0 +1,586 @@
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountSerialize;
+use anchor_spl::token::spl_token;
+use lending::constants::DISCRIMINATOR;
+use lending::decimal::Decimal;
+use lending::enums::OracleType;
+use lending::errors::ErrorCode;
+use lending::states::{Bank, BankConfig, GlobalConfig, InterestRateModel, OracleConfig, User};
+use lending_client::{
+    find_bank_address, find_global_config_address, find_program_data_address,
+    find_receipt_mint_address, find_user_address, get_associated_token_address,
+};
+use pyth_solana_receiver_sdk::price_update::{PriceFeedMessage, PriceUpdateV2, VerificationLevel};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{Account as SolanaAccount, AccountSharedData};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use std::path::{Path, PathBuf};
+
+///SOL/USD feed id
+pub const SOL_FEED_ID: [u8; 32] = [
+    0xef, 0x0d, 0x8b, 0x6f, 0xda, 0x2c, 0xeb, 0xa4, 0x1d, 0xa1, 0x5d, 0x40, 0x95, 0xd1, 0xda, 0x39,
+    0x2a, 0x0d, 0x2f, 0x8e, 0xd0, 0xc6, 0xc7, 0xbc, 0x0f, 0x4c, 0xfa, 0xc8, 0xc2, 0x80, 0xb5, 0x6d,
+];
+
+///USDC/USD feed id
+pub const USDC_FEED_ID: [u8; 32] = [
+    0xea, 0xa0, 0x20, 0xc6, 0x1c, 0xc4, 0x79, 0x71, 0x28, 0x13, 0x46, 0x1c, 0xe1, 0x53, 0x89, 0x4a,
+    0x96, 0xa6, 0xc0, 0x0b, 0x21, 0xed, 0x0c, 0xfc, 0x27, 0x98, 0xd1, 0xf9, 0xa9, 0xe9, 0xc9, 0x4a,
+];
+
+pub const SOL_DECIMALS: u8 = 9;
+pub const USDC_DECIMALS: u8 = 6;
+pub const PRICE_EXPONENT: i32 = -8;
+///$150
+pub const SOL_PRICE: i64 = 15_000_000_000;
+///$1
+pub const USDC_PRICE: i64 = 100_000_000;
+pub const MAX_AGE: u64 = 60;
+
+pub const SOL: u64 = 1_000_000_000;
+pub const USDC: u64 = 1_000_000;
+
+pub fn sol_bank_config() -> BankConfig {
+    BankConfig {
+        liquidate_threshold: Decimal::from_bps(8_000),
+        liquidate_bonus: Decimal::from_bps(500),
+        liquidate_close_factor: Decimal::from_bps(5_000),
+        max_ltv: Decimal::from_bps(7_500),
+        interest_rate_model: InterestRateModel {
+            base_rate: Decimal::from_bps(200),
+            slope1: Decimal::from_bps(1_000),
+            optimal_utilization: Decimal::from_bps(8_000),
+            slope2: Decimal::from_bps(10_000),
+        },
+        reserve_factor: Decimal::from_bps(1_000),
+        deposit_cap: 0,
+        borrow_cap: 0,
+        flash_loan_fee: Decimal::from_bps(9),
+        outflow_window: 0,
+        max_outflow_amount: 0,
+        max_outflow_ratio: Decimal::ZERO,
+    }
+}
+
+pub fn usdc_bank_config() -> BankConfig {
+    BankConfig {
+        liquidate_threshold: Decimal::from_bps(8_500),
+        max_ltv: Decimal::from_bps(8_000),
+        ..sol_bank_config()
+    }
+}
+
+pub fn oracle_config(feed_id: [u8; 32]) -> OracleConfig {
+    OracleConfig {
+        oracle_type: OracleType::PythPull,
+        feed_id,
+        max_age: MAX_AGE,
+    }
+}
+
+///把第一个账户之后的账户和数据原样通过 CPI 转发给 lending 的测试程序
+pub const CPI_PROXY_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
+///原生运行 lending 时的入口
+///
+///anchor 的 entry 要求账户切片与 AccountInfo 生命周期一致, 复制出的 AccountInfo 与原账户共享数据,
+///泄漏后即可获得满足要求的切片
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    lending::entry(program_id, accounts, data)
+}
+
+fn process_cpi_proxy(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction = Instruction {
+        program_id: lending::ID,
+        accounts: accounts[1..]
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+
+    invoke(&instruction, accounts)
+}
+
+///把 lending 指令包装为经 CPI_PROXY_ID 转发的 CPI 调用
+pub fn cpi_proxy(instruction: Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(lending::ID, false)];
+    accounts.extend(instruction.accounts);
+
+    Instruction {
+        program_id: CPI_PROXY_ID,
+        accounts,
+        data: instruction.data,
+    }
+}
+
+///`anchor build` 产出的 lending.so, 可用 SBF_OUT_DIR 指定所在目录
+fn find_program_file() -> Option<PathBuf> {
+    let out_dir = std::env::var("SBF_OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"));
+
+    Some(out_dir.join("lending.so")).filter(|program_file| program_file.exists())
+}
+
+///与 `solana program deploy` 相同, 以可升级程序部署并创建 ProgramData 账户
+fn add_upgradeable_program(
+    program_test: &mut ProgramTest,
+    program_file: &Path,
+    upgrade_authority: Pubkey,
+) {
+    let (program_data_key, _) = find_program_data_address();
+    let rent = Rent::default();
+
+    let data = bincode::serialize(&UpgradeableLoaderState::Program {
+        programdata_address: program_data_key,
+    })
+    .unwrap();
+    program_test.add_account(
+        lending::ID,
+        SolanaAccount {
+            lamports: rent.minimum_balance(data.len()),
+            data,
+            owner: bpf_loader_upgradeable::ID,
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+
+    let mut data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(upgrade_authority),
+    })
+    .unwrap();
+    data.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+    data.extend(std::fs::read(program_file).unwrap());
+    program_test.add_account(
+        program_data_key,
+        SolanaAccount {
+            lamports: rent.minimum_balance(data.len()),
+            data,
+            owner: bpf_loader_upgradeable::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+pub struct TestContext {
+    pub context: ProgramTestContext,
+    ///是否加载了 BPF 产物, 否则为原生运行
+    pub bpf: bool,
+    ///程序升级权限, 同时作为全局配置的 admin
+    pub admin: Keypair,
+    pub sol_mint: Pubkey,
+    pub usdc_mint: Pubkey,
+    pub sol_price_update: Pubkey,
+    pub usdc_price_update: Pubkey,
+}
+
+impl TestContext {
+    ///初始化全局配置和 SOL、USDC 两个 Bank
+    pub async fn new() -> Self {
+        let mut test_context = Self::start().await;
+        let sol_mint = test_context.sol_mint;
+        let usdc_mint = test_context.usdc_mint;
+        test_context
+            .init_bank(sol_mint, oracle_config(SOL_FEED_ID), sol_bank_config())
+            .await
+            .unwrap();
+        test_context
+            .init_bank(usdc_mint, oracle_config(USDC_FEED_ID), usdc_bank_config())
+            .await
+            .unwrap();
+        test_context.refresh_banks().await;
+
+        test_context
+    }
+
+    ///部署程序并初始化全局配置, 不创建 Bank
+    pub async fn start() -> Self {
+        let mut test_context = Self::deploy().await;
+        if test_context.bpf {
+            let admin = test_context.admin.insecure_clone();
+            test_context
+                .init_global_config(&admin, admin.pubkey())
+                .await
+                .unwrap();
+        }
+
+        test_context
+    }
+
+    ///部署程序, 创建 admin、两个 mint 和价格账户
+    ///
+    ///优先加载 BPF 产物, 此时全局配置需调用 init_global_config 创建;
+    ///找不到产物时原生运行, 原生程序没有 ProgramData 账户, 全局配置直接写入创世状态
+    pub async fn deploy() -> Self {
+        let admin = Keypair::new();
+        let program_file = find_program_file();
+        let bpf = program_file.is_some();
+        let mut program_test = match &program_file {
+            Some(program_file) => {
+                let mut program_test = ProgramTest::default();
+                add_upgradeable_program(&mut program_test, program_file, admin.pubkey());
+                program_test
+            }
+            None => {
+                let mut program_test =
+                    ProgramTest::new("lending", lending::ID, processor!(process_instruction));
+                program_test.prefer_bpf(false);
+                add_global_config(&mut program_test, admin.pubkey());
+                program_test
+            }
+        };
+        program_test.add_program("cpi_proxy", CPI_PROXY_ID, processor!(process_cpi_proxy));
+
+        program_test.add_account(
+            admin.pubkey(),
+            SolanaAccount::new(1_000 * SOL, 0, &anchor_lang::system_program::ID),
+        );
+
+        let sol_mint = Pubkey::new_unique();
+        let usdc_mint = Pubkey::new_unique();
+        for (mint, decimals) in [(sol_mint, SOL_DECIMALS), (usdc_mint, USDC_DECIMALS)] {
+            add_mint(&mut program_test, mint, decimals);
+        }
+
+        let context = program_test.start_with_context().await;
+        let mut test_context = Self {
+            context,
+            bpf,
+            admin,
+            sol_mint,
+            usdc_mint,
+            sol_price_update: Pubkey::new_unique(),
+            usdc_price_update: Pubkey::new_unique(),
+        };
+        test_context.refresh_prices(SOL_PRICE, USDC_PRICE).await;
+
+        test_context
+    }
+
+    pub async fn init_global_config(
+        &mut self,
+        signer: &Keypair,
+        admin: Pubkey,
+    ) -> std::result::Result<(), BanksClientError> {
+        let instruction = lending_client::init_global_config(signer.pubkey(), admin, admin);
+        self.process(&[instruction], &[signer]).await
+    }
+
+    ///运行时新建一个 mint
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        mint_state(decimals).pack_into_slice(&mut data);
+        self.context.set_account(
+            &mint,
+            &AccountSharedData::from(SolanaAccount {
+                lamports: SOL,
+                data,
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            }),
+        );
+
+        mint
+    }
+
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers: Vec<&Keypair> = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    pub async fn init_bank(
+        &mut self,
+        mint: Pubkey,
+        oracle_config: OracleConfig,
+        bank_config: BankConfig,
+    ) -> std::result::Result<(), BanksClientError> {
+        let instruction = lending_client::init_bank(
+            self.admin.pubkey(),
+            mint,
+            spl_token::ID,
+            oracle_config,
+            bank_config,
+        );
+        let admin = self.admin.insecure_clone();
+        self.process(&[instruction], &[&admin]).await
+    }
+
+    ///创建用户账户并在其 ATA 中放入代币
+    pub async fn create_user(&mut self, sol_amount: u64, usdc_amount: u64) -> Keypair {
+        let user = Keypair::new();
+        let transfer =
+            system_instruction::transfer(&self.context.payer.pubkey(), &user.pubkey(), 10 * SOL);
+        self.process(&[transfer], &[]).await.unwrap();
+        self.process(&[lending_client::init_user(user.pubkey())], &[&user])
+            .await
+            .unwrap();
+        let (sol_mint, usdc_mint) = (self.sol_mint, self.usdc_mint);
+        self.set_token_balance(&user.pubkey(), &sol_mint, sol_amount);
+        self.set_token_balance(&user.pubkey(), &usdc_mint, usdc_amount);
+
+        user
+    }
+
+    pub fn set_token_balance(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) {
+        let token_account = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            delegate: COption::None,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        token_account.pack_into_slice(&mut data);
+        self.context.set_account(
+            &get_associated_token_address(owner, mint, &spl_token::ID),
+            &AccountSharedData::from(SolanaAccount {
+                lamports: SOL,
+                data,
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            }),
+        );
+    }
+
+    pub async fn token_balance(&mut self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(get_associated_token_address(owner, mint, &spl_token::ID))
+            .await
+            .unwrap()
+            .unwrap();
+
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    ///存款凭证总供应量
+    pub async fn receipt_supply(&mut self, mint: &Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(find_receipt_mint_address(mint).0)
+            .await
+            .unwrap()
+            .unwrap();
+
+        spl_token::state::Mint::unpack(&account.data)
+            .unwrap()
+            .supply
+    }
+
+    pub async fn global_config(&mut self) -> GlobalConfig {
+        let account = self
+            .context
+            .banks_client
+            .get_account(find_global_config_address().0)
+            .await
+            .unwrap()
+            .unwrap();
+
+        GlobalConfig::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn bank(&mut self, mint: &Pubkey) -> Bank {
+        let account = self
+            .context
+            .banks_client
+            .get_account(find_bank_address(mint).0)
+            .await
+            .unwrap()
+            .unwrap();
+
+        lending_client::deserialize_bank(&account.data).unwrap()
+    }
+
+    pub async fn user(&mut self, owner: &Pubkey) -> User {
+        let account = self
+            .context
+            .banks_client
+            .get_account(find_user_address(owner).0)
+            .await
+            .unwrap()
+            .unwrap();
+
+        lending_client::deserialize_user(&account.data).unwrap()
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+    }
+
+    pub async fn unix_timestamp(&mut self) -> i64 {
+        self.clock().await.unix_timestamp
+    }
+
+    pub async fn set_price(&mut self, price_update: Pubkey, feed_id: [u8; 32], price: i64) {
+        let publish_time = self.unix_timestamp().await;
+        let price_update_v2 = PriceUpdateV2 {
+            write_authority: Pubkey::default(),
+            verification_level: VerificationLevel::Full,
+            price_message: PriceFeedMessage {
+                feed_id,
+                price,
+                conf: 0,
+                exponent: PRICE_EXPONENT,
+                publish_time,
+                prev_publish_time: publish_time - 1,
+                ema_price: price,
+                ema_conf: 0,
+            },
+            posted_slot: 0,
+        };
+        let mut data = Vec::with_capacity(PriceUpdateV2::LEN);
+        price_update_v2.try_serialize(&mut data).unwrap();
+        self.context.set_account(
+            &price_update,
+            &AccountSharedData::from(SolanaAccount {
+                lamports: SOL,
+                data,
+                owner: pyth_solana_receiver_sdk::ID,
+                executable: false,
+                rent_epoch: 0,
+            }),
+        );
+    }
+
+    pub async fn refresh_prices(&mut self, sol_price: i64, usdc_price: i64) {
+        let (sol_price_update, usdc_price_update) = (self.sol_price_update, self.usdc_price_update);
+        self.set_price(sol_price_update, SOL_FEED_ID, sol_price)
+            .await;
+        self.set_price(usdc_price_update, USDC_FEED_ID, usdc_price)
+            .await;
+    }
+
+    ///调用 refresh_bank 结算两个 Bank 的利息并缓存当前价格, remaining_accounts 中的 Bank 使用缓存价格
+    pub async fn refresh_banks(&mut self) {
+        let refresh_sol = lending_client::refresh_bank(self.sol_mint, self.sol_price_update);
+        let refresh_usdc = lending_client::refresh_bank(self.usdc_mint, self.usdc_price_update);
+        self.process(&[refresh_sol, refresh_usdc], &[])
+            .await
+            .unwrap();
+    }
+
+    ///时钟前进 seconds 秒, 并以默认价格刷新价格账户
+    pub async fn warp_seconds(&mut self, seconds: i64) {
+        let mut clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+        self.refresh_prices(SOL_PRICE, USDC_PRICE).await;
+    }
+}
+
+fn add_global_config(program_test: &mut ProgramTest, admin: Pubkey) {
+    let (global_config_key, bump) = find_global_config_address();
+    let global_config = GlobalConfig {
+        admin,
+        fee_recipient: admin,
+        bump,
+        ..Default::default()
+    };
+    let mut data = Vec::with_capacity(DISCRIMINATOR + GlobalConfig::INIT_SPACE);
+    global_config.try_serialize(&mut data).unwrap();
+    data.resize(DISCRIMINATOR + GlobalConfig::INIT_SPACE, 0);
+    program_test.add_account(
+        global_config_key,
+        SolanaAccount {
+            lamports: SOL,
+            data,
+            owner: lending::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+fn mint_state(decimals: u8) -> spl_token::state::Mint {
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply: 0,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+}
+
+fn add_mint(program_test: &mut ProgramTest, mint: Pubkey, decimals: u8) {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    mint_state(decimals).pack_into_slice(&mut data);
+    program_test.add_account(
+        mint,
+        SolanaAccount {
+            lamports: SOL,
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+pub fn assert_error(result: std::result::Result<(), BanksClientError>, error_code: ErrorCode) {
+    let expected: u32 = error_code.into();
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, expected, "expected {error_code:?}")
+        }
+        error => panic!("expected {error_code:?}, got {error:?}"),
+    }
+}
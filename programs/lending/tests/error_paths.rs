@@ -0,0 +1,957 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use common::*;
+use lending::constants::{PAUSE_DEPOSIT, PAUSE_EMERGENCY, PAUSE_WITHDRAW, SECONDS_PER_YEAR};
+use lending::decimal::Decimal;
+use lending::enums::Rounding;
+use lending::errors::ErrorCode;
+use lending::math_utils::calc_virtual_deposit_shares;
+use lending_client::{find_bank_address, get_associated_token_address};
+use solana_sdk::signature::{Keypair, Signer};
+
+///出借人存入 10000 USDC, 借款人存入 10 SOL 并借出 1000 USDC
+async fn setup_borrower(test_context: &mut TestContext) -> (Keypair, Keypair) {
+    let (sol_mint, usdc_mint) = (test_context.sol_mint, test_context.usdc_mint);
+    let lender = test_context.create_user(0, 10_000 * USDC).await;
+    let borrower = test_context.create_user(10 * SOL, 0).await;
+    let deposit = lending_client::deposit(
+        lender.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        10_000 * USDC,
+        &[],
+    );
+    test_context.process(&[deposit], &[&lender]).await.unwrap();
+    let deposit = lending_client::deposit(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        10 * SOL,
+        &[],
+    );
+    test_context
+        .process(&[deposit], &[&borrower])
+        .await
+        .unwrap();
+    test_context
+        .process(&[borrow_usdc(test_context, &borrower, 1_000)], &[&borrower])
+        .await
+        .unwrap();
+
+    (lender, borrower)
+}
+
+fn borrow_usdc(
+    test_context: &TestContext,
+    borrower: &Keypair,
+    value: u64,
+) -> solana_sdk::instruction::Instruction {
+    lending_client::borrow(
+        borrower.pubkey(),
+        test_context.usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        Decimal::from_u64(value),
+        &[find_bank_address(&test_context.sol_mint).0],
+    )
+}
+
+fn liquidate(
+    test_context: &TestContext,
+    liquidator: &Keypair,
+    borrower: &Keypair,
+    to_repay_amount: u64,
+) -> solana_sdk::instruction::Instruction {
+    lending_client::liquidate(
+        liquidator.pubkey(),
+        borrower.pubkey(),
+        test_context.sol_mint,
+        test_context.usdc_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        test_context.usdc_price_update,
+        to_repay_amount,
+        &[],
+    )
+}
+
+///BuilderError 无法由外部输入触发, 不在此覆盖
+
+//原生运行时没有 ProgramData 账户, 全局配置已写入创世状态, 需 anchor build 产出的 .so
+#[tokio::test]
+#[ignore = "requires anchor build"]
+async fn init_global_config_errors() {
+    let mut test_context = TestContext::deploy().await;
+    assert!(
+        test_context.bpf,
+        "lending.so not found, run anchor build first"
+    );
+    let admin = test_context.admin.insecure_clone();
+    let stranger = test_context.create_user(0, 0).await;
+
+    let result = test_context
+        .init_global_config(&stranger, stranger.pubkey())
+        .await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    test_context
+        .init_global_config(&admin, admin.pubkey())
+        .await
+        .unwrap();
+    let global_config = test_context.global_config().await;
+    assert_eq!(global_config.admin, admin.pubkey());
+    assert_eq!(global_config.fee_recipient, admin.pubkey());
+}
+
+#[test]
+fn divide_by_zero() {
+    let result = Decimal::ONE.try_div(Decimal::ZERO, Rounding::Down);
+    assert_eq!(result.unwrap_err(), ErrorCode::DivideByZero.into());
+}
+
+#[tokio::test]
+async fn init_bank_errors() {
+    let mut test_context = TestContext::start().await;
+    let sol_mint = test_context.sol_mint;
+
+    let result = test_context
+        .init_bank(sol_mint, oracle_config([0; 32]), sol_bank_config())
+        .await;
+    assert_error(result, ErrorCode::InvalidOracleConfig);
+
+    let mut bank_config = sol_bank_config();
+    bank_config.max_ltv = bank_config.liquidate_threshold;
+    let result = test_context
+        .init_bank(sol_mint, oracle_config(SOL_FEED_ID), bank_config)
+        .await;
+    assert_error(result, ErrorCode::InvalidBankConfig);
+
+    let mut bank_config = sol_bank_config();
+    bank_config.liquidate_bonus = Decimal::from_bps(2_000);
+    let result = test_context
+        .init_bank(sol_mint, oracle_config(SOL_FEED_ID), bank_config)
+        .await;
+    assert_error(result, ErrorCode::InvalidBankConfig);
+
+    let stranger = test_context.create_user(0, 0).await;
+    let instruction = lending_client::init_bank(
+        stranger.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        oracle_config(SOL_FEED_ID),
+        sol_bank_config(),
+    );
+    let result = test_context.process(&[instruction], &[&stranger]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn update_bank_config_errors() {
+    let mut test_context = TestContext::new().await;
+    let sol_mint = test_context.sol_mint;
+    let admin = test_context.admin.insecure_clone();
+
+    let stranger = Keypair::new();
+    let instruction =
+        lending_client::update_bank_config(stranger.pubkey(), sol_mint, sol_bank_config());
+    let result = test_context.process(&[instruction], &[&stranger]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    let mut bank_config = sol_bank_config();
+    bank_config.liquidate_threshold = Decimal::from_bps(10_001);
+    let instruction = lending_client::update_bank_config(admin.pubkey(), sol_mint, bank_config);
+    let result = test_context.process(&[instruction], &[&admin]).await;
+    assert_error(result, ErrorCode::InvalidBankConfig);
+
+    let mut bank_config = sol_bank_config();
+    bank_config.max_ltv = Decimal::from_bps(5_000);
+    let instruction = lending_client::update_bank_config(admin.pubkey(), sol_mint, bank_config);
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context.bank(&sol_mint).await.max_ltv,
+        Decimal::from_bps(5_000)
+    );
+}
+
+#[tokio::test]
+async fn bank_authority_transfer() {
+    let mut test_context = TestContext::new().await;
+    let sol_mint = test_context.sol_mint;
+    let admin = test_context.admin.insecure_clone();
+    let (first, second) = (Keypair::new(), Keypair::new());
+
+    let instruction =
+        lending_client::propose_bank_authority(first.pubkey(), sol_mint, first.pubkey());
+    let result = test_context.process(&[instruction], &[&first]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    //新的提议覆盖尚未接受的提议
+    let instruction =
+        lending_client::propose_bank_authority(admin.pubkey(), sol_mint, first.pubkey());
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    let instruction =
+        lending_client::propose_bank_authority(admin.pubkey(), sol_mint, second.pubkey());
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context.bank(&sol_mint).await.pending_authority,
+        second.pubkey()
+    );
+
+    let instruction = lending_client::accept_bank_authority(first.pubkey(), sol_mint);
+    let result = test_context.process(&[instruction], &[&first]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    let instruction = lending_client::accept_bank_authority(second.pubkey(), sol_mint);
+    test_context
+        .process(&[instruction], &[&second])
+        .await
+        .unwrap();
+    let bank = test_context.bank(&sol_mint).await;
+    assert_eq!(bank.authority, second.pubkey());
+    assert_eq!(bank.pending_authority, Pubkey::default());
+
+    let instruction =
+        lending_client::update_bank_config(admin.pubkey(), sol_mint, sol_bank_config());
+    let result = test_context.process(&[instruction], &[&admin]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+    let instruction =
+        lending_client::update_bank_config(second.pubkey(), sol_mint, sol_bank_config());
+    test_context
+        .process(&[instruction], &[&second])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn admin_transfer() {
+    let mut test_context = TestContext::new().await;
+    let admin = test_context.admin.insecure_clone();
+    let (first, second) = (Keypair::new(), Keypair::new());
+
+    let instruction = lending_client::propose_admin(first.pubkey(), first.pubkey());
+    let result = test_context.process(&[instruction], &[&first]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    //新的提议覆盖尚未接受的提议
+    let instruction = lending_client::propose_admin(admin.pubkey(), first.pubkey());
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    let instruction = lending_client::propose_admin(admin.pubkey(), second.pubkey());
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context.global_config().await.pending_admin,
+        second.pubkey()
+    );
+
+    let instruction = lending_client::accept_admin(first.pubkey());
+    let result = test_context.process(&[instruction], &[&first]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    let instruction = lending_client::accept_admin(second.pubkey());
+    test_context
+        .process(&[instruction], &[&second])
+        .await
+        .unwrap();
+    let global_config = test_context.global_config().await;
+    assert_eq!(global_config.admin, second.pubkey());
+    assert_eq!(global_config.pending_admin, Pubkey::default());
+
+    let instruction = lending_client::set_guardian(admin.pubkey(), admin.pubkey());
+    let result = test_context.process(&[instruction], &[&admin]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+    let instruction = lending_client::set_guardian(second.pubkey(), admin.pubkey());
+    test_context
+        .process(&[instruction], &[&second])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn deposit_errors() {
+    let mut test_context = TestContext::new().await;
+    let sol_mint = test_context.sol_mint;
+    let user = test_context.create_user(10 * SOL, 0).await;
+
+    let deposit = lending_client::deposit(
+        user.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        0,
+        &[],
+    );
+    let result = test_context.process(&[deposit], &[&user]).await;
+    assert_error(result, ErrorCode::DepositedAmountLessOrEqualZero);
+
+    let whale = test_context.create_user(0, 0).await;
+    let whale_amount = u64::MAX - calc_virtual_deposit_shares(SOL_DECIMALS).unwrap();
+    test_context.set_token_balance(&whale.pubkey(), &sol_mint, whale_amount);
+    let deposit = lending_client::deposit(
+        whale.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        whale_amount,
+        &[],
+    );
+    test_context.process(&[deposit], &[&whale]).await.unwrap();
+    let deposit = lending_client::deposit(
+        user.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        SOL,
+        &[],
+    );
+    let result = test_context.process(&[deposit], &[&user]).await;
+    assert_error(result, ErrorCode::MathOverflow);
+}
+
+#[tokio::test]
+async fn deposit_rounding_to_zero_shares() {
+    let mut test_context = TestContext::new().await;
+    let usdc_mint = test_context.usdc_mint;
+    let (lender, _) = setup_borrower(&mut test_context).await;
+    test_context.warp_seconds(SECONDS_PER_YEAR as i64).await;
+
+    //supply_index > 1 后, 1 个最小单位换不到份额
+    let deposit = lending_client::deposit(
+        lender.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        1,
+        &[],
+    );
+    let result = test_context.process(&[deposit], &[&lender]).await;
+    assert_error(result, ErrorCode::DepositTooSmall);
+}
+
+#[tokio::test]
+async fn deposit_and_borrow_caps() {
+    let mut test_context = TestContext::new().await;
+    let (sol_mint, usdc_mint) = (test_context.sol_mint, test_context.usdc_mint);
+    let admin = test_context.admin.insecure_clone();
+    let (_, borrower) = setup_borrower(&mut test_context).await;
+
+    //虚拟存款不占用存款上限, 用户存款可以恰好达到上限
+    let mut bank_config = sol_bank_config();
+    bank_config.deposit_cap = 11 * SOL;
+    let instruction = lending_client::update_bank_config(admin.pubkey(), sol_mint, bank_config);
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    test_context.set_token_balance(&borrower.pubkey(), &sol_mint, 2 * SOL);
+    let deposit = lending_client::deposit(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        SOL,
+        &[find_bank_address(&usdc_mint).0],
+    );
+    test_context
+        .process(&[deposit], &[&borrower])
+        .await
+        .unwrap();
+    let deposit = lending_client::deposit(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        1,
+        &[find_bank_address(&usdc_mint).0],
+    );
+    let result = test_context.process(&[deposit], &[&borrower]).await;
+    assert_error(result, ErrorCode::DepositCapExceeded);
+
+    let mut bank_config = usdc_bank_config();
+    bank_config.borrow_cap = 1_050 * USDC;
+    let instruction = lending_client::update_bank_config(admin.pubkey(), usdc_mint, bank_config);
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    let borrow = borrow_usdc(&test_context, &borrower, 100);
+    let result = test_context.process(&[borrow], &[&borrower]).await;
+    assert_error(result, ErrorCode::BorrowCapExceeded);
+}
+
+#[tokio::test]
+async fn borrow_errors() {
+    let mut test_context = TestContext::new().await;
+    let (sol_mint, usdc_mint) = (test_context.sol_mint, test_context.usdc_mint);
+    let (_, borrower) = setup_borrower(&mut test_context).await;
+
+    let no_collateral = test_context.create_user(0, 0).await;
+    let borrow = lending_client::borrow(
+        no_collateral.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        Decimal::from_u64(1),
+        &[],
+    );
+    let result = test_context.process(&[borrow], &[&no_collateral]).await;
+    assert_error(result, ErrorCode::DepositedValueLessOrEqualZero);
+
+    //10 SOL * $150 * 0.75 = $1125, 已借 $1000
+    let borrow = borrow_usdc(&test_context, &borrower, 200);
+    let result = test_context.process(&[borrow], &[&borrower]).await;
+    assert_error(result, ErrorCode::ExceedMaxLtv);
+
+    let whale = test_context.create_user(1_000 * SOL, 0).await;
+    let deposit = lending_client::deposit(
+        whale.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        1_000 * SOL,
+        &[],
+    );
+    test_context.process(&[deposit], &[&whale]).await.unwrap();
+    let borrow = borrow_usdc(&test_context, &whale, 20_000);
+    let result = test_context.process(&[borrow], &[&whale]).await;
+    assert_error(result, ErrorCode::InsufficientLiquidity);
+
+    let borrow = lending_client::borrow(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        Decimal::from_u64(1),
+        &[],
+    );
+    let result = test_context.process(&[borrow], &[&borrower]).await;
+    assert_error(result, ErrorCode::InvalidRemainingAccounts);
+
+    let borrow = lending_client::borrow(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        Decimal::from_u64(1),
+        &[find_bank_address(&sol_mint).0],
+    );
+    let result = test_context.process(&[borrow], &[&borrower]).await;
+    assert_error(result, ErrorCode::InvalidOracleAccount);
+
+    test_context.refresh_prices(SOL_PRICE, 0).await;
+    let borrow = borrow_usdc(&test_context, &borrower, 1);
+    let result = test_context.process(&[borrow], &[&borrower]).await;
+    assert_error(result, ErrorCode::InvalidPrice);
+}
+
+#[tokio::test]
+async fn stale_price_cache() {
+    let mut test_context = TestContext::new().await;
+    let (_, borrower) = setup_borrower(&mut test_context).await;
+
+    //价格账户已更新, 但 remaining_accounts 中 SOL Bank 的缓存价格超过 max_age
+    test_context.warp_seconds(MAX_AGE as i64 + 1).await;
+    let borrow = borrow_usdc(&test_context, &borrower, 1);
+    let result = test_context.process(&[borrow], &[&borrower]).await;
+    assert_error(result, ErrorCode::StalePriceCache);
+
+    test_context.refresh_banks().await;
+    let borrow = borrow_usdc(&test_context, &borrower, 2);
+    test_context.process(&[borrow], &[&borrower]).await.unwrap();
+}
+
+#[tokio::test]
+async fn repay_errors() {
+    let mut test_context = TestContext::new().await;
+    let usdc_mint = test_context.usdc_mint;
+    let (lender, borrower) = setup_borrower(&mut test_context).await;
+
+    let repay = lending_client::repay(
+        lender.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        USDC,
+        &[],
+    );
+    let result = test_context.process(&[repay], &[&lender]).await;
+    assert_error(result, ErrorCode::NoNeedToRepay);
+
+    test_context.set_token_balance(&borrower.pubkey(), &usdc_mint, 2_000 * USDC);
+    let repay = lending_client::repay(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        1_001 * USDC,
+        &[],
+    );
+    let result = test_context.process(&[repay], &[&borrower]).await;
+    assert_error(result, ErrorCode::RepayExceedBorrowed);
+}
+
+#[tokio::test]
+async fn withdraw_errors() {
+    let mut test_context = TestContext::new().await;
+    let sol_mint = test_context.sol_mint;
+    let (_, borrower) = setup_borrower(&mut test_context).await;
+    let remaining_accounts = [find_bank_address(&test_context.usdc_mint).0];
+
+    let withdraw = lending_client::withdraw(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        11 * SOL,
+        &remaining_accounts,
+    );
+    let result = test_context.process(&[withdraw], &[&borrower]).await;
+    assert_error(result, ErrorCode::NotEnoughBalance);
+
+    let withdraw = lending_client::withdraw(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        2 * SOL,
+        &remaining_accounts,
+    );
+    let result = test_context.process(&[withdraw], &[&borrower]).await;
+    assert_error(result, ErrorCode::ExceedMaxLtv);
+
+    let withdraw = lending_client::withdraw(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        SOL / 2,
+        &remaining_accounts,
+    );
+    test_context
+        .process(&[withdraw], &[&borrower])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn liquidate_errors_and_success() {
+    let mut test_context = TestContext::new().await;
+    let (sol_mint, usdc_mint) = (test_context.sol_mint, test_context.usdc_mint);
+    let (_, borrower) = setup_borrower(&mut test_context).await;
+    let liquidator = test_context.create_user(0, 1_000 * USDC).await;
+
+    let instruction = liquidate(&test_context, &liquidator, &borrower, 100 * USDC);
+    let result = test_context.process(&[instruction], &[&liquidator]).await;
+    assert_error(result, ErrorCode::NotLiquidatable);
+
+    //SOL 跌到 $120, 健康因子 = 1200 * 0.8 / 1000 < 1
+    test_context
+        .refresh_prices(12_000_000_000, USDC_PRICE)
+        .await;
+    let instruction = liquidate(&test_context, &liquidator, &borrower, 501 * USDC);
+    let result = test_context.process(&[instruction], &[&liquidator]).await;
+    assert_error(result, ErrorCode::LiquidateExceedCloseFactor);
+
+    let instruction = liquidate(&test_context, &liquidator, &borrower, 120 * USDC);
+    test_context
+        .process(&[instruction], &[&liquidator])
+        .await
+        .unwrap();
+    //120 * 1.05 / 120 = 1.05 SOL
+    assert_eq!(
+        test_context
+            .token_balance(&liquidator.pubkey(), &sol_mint)
+            .await,
+        1_050_000_000
+    );
+    assert_eq!(
+        test_context
+            .token_balance(&liquidator.pubkey(), &usdc_mint)
+            .await,
+        880 * USDC
+    );
+
+    //SOL 跌到 $50, 剩余抵押品 8.95 SOL = $447.5 < $440 * 1.05
+    test_context.refresh_prices(5_000_000_000, USDC_PRICE).await;
+    let instruction = liquidate(&test_context, &liquidator, &borrower, 440 * USDC);
+    let result = test_context.process(&[instruction], &[&liquidator]).await;
+    assert_error(result, ErrorCode::NotEnoughLiquidationAssets);
+}
+
+#[tokio::test]
+async fn too_many_positions() {
+    let mut test_context = TestContext::new().await;
+    let user = test_context.create_user(0, 0).await;
+
+    for index in 0..=lending::constants::MAX_POSITIONS {
+        let mint = test_context.create_mint(SOL_DECIMALS);
+        test_context
+            .init_bank(mint, oracle_config(SOL_FEED_ID), sol_bank_config())
+            .await
+            .unwrap();
+        test_context.set_token_balance(&user.pubkey(), &mint, SOL);
+        let deposit = lending_client::deposit(
+            user.pubkey(),
+            mint,
+            spl_token::ID,
+            test_context.sol_price_update,
+            SOL,
+            &[],
+        );
+        let result = test_context.process(&[deposit], &[&user]).await;
+        if index < lending::constants::MAX_POSITIONS {
+            result.unwrap();
+        } else {
+            assert_error(result, ErrorCode::TooManyPositions);
+        }
+    }
+}
+
+#[tokio::test]
+async fn pause_flags() {
+    let mut test_context = TestContext::new().await;
+    let (sol_mint, usdc_mint) = (test_context.sol_mint, test_context.usdc_mint);
+    let admin = test_context.admin.insecure_clone();
+    let guardian = Keypair::new();
+    let (_, borrower) = setup_borrower(&mut test_context).await;
+
+    let instruction = lending_client::set_guardian(admin.pubkey(), guardian.pubkey());
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+
+    let instruction = lending_client::set_pause_flags(admin.pubkey(), Some(sol_mint), 1 << 63);
+    let result = test_context.process(&[instruction], &[&admin]).await;
+    assert_error(result, ErrorCode::InvalidPauseFlags);
+
+    let instruction =
+        lending_client::set_pause_flags(guardian.pubkey(), Some(sol_mint), PAUSE_DEPOSIT);
+    test_context
+        .process(&[instruction], &[&guardian])
+        .await
+        .unwrap();
+    test_context.set_token_balance(&borrower.pubkey(), &sol_mint, SOL);
+    let deposit = lending_client::deposit(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        SOL,
+        &[],
+    );
+    let result = test_context.process(&[deposit], &[&borrower]).await;
+    assert_error(result, ErrorCode::OperationPaused);
+
+    //锁定抵押品与存款共用暂停标志, 解锁不受影响
+    let usdc_bank = find_bank_address(&usdc_mint).0;
+    let unlock = lending_client::unlock_collateral(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        SOL / 10,
+        &[usdc_bank],
+    );
+    test_context.process(&[unlock], &[&borrower]).await.unwrap();
+    let lock = lending_client::lock_collateral(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        SOL / 10,
+        &[usdc_bank],
+    );
+    let result = test_context
+        .process(std::slice::from_ref(&lock), &[&borrower])
+        .await;
+    assert_error(result, ErrorCode::OperationPaused);
+
+    let instruction = lending_client::set_pause_flags(guardian.pubkey(), Some(sol_mint), 0);
+    let result = test_context.process(&[instruction], &[&guardian]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    let stranger = Keypair::new();
+    let instruction = lending_client::set_pause_flags(stranger.pubkey(), None, PAUSE_DEPOSIT);
+    let result = test_context.process(&[instruction], &[&stranger]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    let instruction = lending_client::set_pause_flags(admin.pubkey(), Some(sol_mint), 0);
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    test_context.process(&[lock], &[&borrower]).await.unwrap();
+
+    let instruction =
+        lending_client::set_pause_flags(admin.pubkey(), Some(sol_mint), PAUSE_WITHDRAW);
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    let unlock = lending_client::unlock_collateral(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        SOL / 10,
+        &[usdc_bank],
+    );
+    let result = test_context.process(&[unlock], &[&borrower]).await;
+    assert_error(result, ErrorCode::OperationPaused);
+    let instruction = lending_client::set_pause_flags(admin.pubkey(), Some(sol_mint), 0);
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+
+    //紧急模式下仍可还款, 但不能借款
+    let instruction = lending_client::set_pause_flags(guardian.pubkey(), None, PAUSE_EMERGENCY);
+    test_context
+        .process(&[instruction], &[&guardian])
+        .await
+        .unwrap();
+    let borrow = borrow_usdc(&test_context, &borrower, 1);
+    let result = test_context.process(&[borrow], &[&borrower]).await;
+    assert_error(result, ErrorCode::OperationPaused);
+    let repay = lending_client::repay(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        100 * USDC,
+        &[find_bank_address(&sol_mint).0],
+    );
+    test_context.process(&[repay], &[&borrower]).await.unwrap();
+}
+
+#[tokio::test]
+async fn protocol_fees() {
+    let mut test_context = TestContext::new().await;
+    let usdc_mint = test_context.usdc_mint;
+    let admin = test_context.admin.insecure_clone();
+    setup_borrower(&mut test_context).await;
+    let previous_bank = test_context.bank(&usdc_mint).await;
+    test_context.warp_seconds(30 * 24 * 60 * 60).await;
+
+    //协议收入只来自借款指数增长, 不受 total_borrowed_amount 的取整影响
+    let refresh = lending_client::refresh_bank(usdc_mint, test_context.usdc_price_update);
+    test_context.process(&[refresh], &[]).await.unwrap();
+    let bank = test_context.bank(&usdc_mint).await;
+    let protocol_fees = Decimal::from_u64(previous_bank.total_borrowed_shares)
+        .try_mul(
+            bank.borrow_index
+                .try_sub(previous_bank.borrow_index)
+                .unwrap(),
+            Rounding::Down,
+        )
+        .unwrap()
+        .try_mul(bank.reserve_factor, Rounding::Down)
+        .unwrap()
+        .try_to_u64(Rounding::Down)
+        .unwrap();
+    assert!(protocol_fees > 0);
+    assert_eq!(
+        bank.accumulated_protocol_fees - previous_bank.accumulated_protocol_fees,
+        protocol_fees
+    );
+
+    let instruction = lending_client::withdraw_protocol_fees(
+        admin.pubkey(),
+        admin.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        1_000 * USDC,
+    );
+    let result = test_context.process(&[instruction], &[&admin]).await;
+    assert_error(result, ErrorCode::ExceedProtocolFees);
+
+    let instruction = lending_client::withdraw_protocol_fees(
+        admin.pubkey(),
+        admin.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        1,
+    );
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context
+            .token_balance(&admin.pubkey(), &usdc_mint)
+            .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn flash_loan() {
+    let mut test_context = TestContext::new().await;
+    let usdc_mint = test_context.usdc_mint;
+    let (lender, _) = setup_borrower(&mut test_context).await;
+    let user_token_account =
+        get_associated_token_address(&lender.pubkey(), &usdc_mint, &spl_token::ID);
+    let flash_borrow = |amount| {
+        lending_client::flash_borrow(
+            lender.pubkey(),
+            usdc_mint,
+            spl_token::ID,
+            user_token_account,
+            amount,
+        )
+    };
+    let flash_repay = lending_client::flash_repay(
+        lender.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        user_token_account,
+    );
+
+    let result = test_context
+        .process(&[flash_borrow(USDC)], &[&lender])
+        .await;
+    assert_error(result, ErrorCode::FlashRepayNotFound);
+
+    let result = test_context
+        .process(std::slice::from_ref(&flash_repay), &[&lender])
+        .await;
+    assert_error(result, ErrorCode::NoFlashLoanInProgress);
+
+    let result = test_context
+        .process(
+            &[
+                flash_borrow(USDC),
+                flash_borrow(2 * USDC),
+                flash_repay.clone(),
+            ],
+            &[&lender],
+        )
+        .await;
+    assert_error(result, ErrorCode::FlashLoanInProgress);
+
+    let result = test_context
+        .process(
+            &[cpi_proxy(flash_borrow(USDC)), flash_repay.clone()],
+            &[&lender],
+        )
+        .await;
+    assert_error(result, ErrorCode::FlashLoanCpiNotAllowed);
+
+    //手续费 0.09%
+    test_context.set_token_balance(&lender.pubkey(), &usdc_mint, 10 * USDC);
+    let supply_index = test_context.bank(&usdc_mint).await.supply_index;
+    test_context
+        .process(&[flash_borrow(1_000 * USDC), flash_repay], &[&lender])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context
+            .token_balance(&lender.pubkey(), &usdc_mint)
+            .await,
+        10 * USDC - 900_000
+    );
+    assert!(test_context.bank(&usdc_mint).await.supply_index > supply_index);
+}
+
+#[tokio::test]
+async fn outflow_limit() {
+    let mut test_context = TestContext::new().await;
+    let usdc_mint = test_context.usdc_mint;
+    let admin = test_context.admin.insecure_clone();
+    let (lender, borrower) = setup_borrower(&mut test_context).await;
+    let usdc_price_update = test_context.usdc_price_update;
+    let withdraw = |amount| {
+        lending_client::withdraw(
+            lender.pubkey(),
+            usdc_mint,
+            spl_token::ID,
+            usdc_price_update,
+            amount,
+            &[],
+        )
+    };
+
+    let mut bank_config = usdc_bank_config();
+    bank_config.outflow_window = 60 * 60;
+    bank_config.max_outflow_amount = 1_000 * USDC;
+    let instruction = lending_client::update_bank_config(admin.pubkey(), usdc_mint, bank_config);
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+
+    test_context
+        .process(&[withdraw(600 * USDC)], &[&lender])
+        .await
+        .unwrap();
+    let result = test_context
+        .process(&[withdraw(500 * USDC)], &[&lender])
+        .await;
+    assert_error(result, ErrorCode::OutflowLimitExceeded);
+
+    //还款抵扣当前窗口的净流出
+    let repay = lending_client::repay(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        100 * USDC,
+        &[find_bank_address(&test_context.sol_mint).0],
+    );
+    test_context.process(&[repay], &[&borrower]).await.unwrap();
+    test_context
+        .process(&[withdraw(500 * USDC)], &[&lender])
+        .await
+        .unwrap();
+    let result = test_context.process(&[withdraw(1)], &[&lender]).await;
+    assert_error(result, ErrorCode::OutflowLimitExceeded);
+
+    test_context.warp_seconds(2 * 60 * 60).await;
+    test_context
+        .process(&[withdraw(1_000 * USDC)], &[&lender])
+        .await
+        .unwrap();
+
+    //剩余流动性约 7000 USDC, 每个窗口最多流出 5%
+    test_context.warp_seconds(2 * 60 * 60).await;
+    let mut bank_config = usdc_bank_config();
+    bank_config.outflow_window = 60 * 60;
+    bank_config.max_outflow_ratio = Decimal::from_bps(500);
+    let instruction = lending_client::update_bank_config(admin.pubkey(), usdc_mint, bank_config);
+    test_context
+        .process(&[instruction], &[&admin])
+        .await
+        .unwrap();
+    let result = test_context
+        .process(&[withdraw(400 * USDC)], &[&lender])
+        .await;
+    assert_error(result, ErrorCode::OutflowLimitExceeded);
+    test_context
+        .process(&[withdraw(300 * USDC)], &[&lender])
+        .await
+        .unwrap();
+
+    let mut bank_config = usdc_bank_config();
+    bank_config.outflow_window = -1;
+    let instruction = lending_client::update_bank_config(admin.pubkey(), usdc_mint, bank_config);
+    let result = test_context.process(&[instruction], &[&admin]).await;
+    assert_error(result, ErrorCode::InvalidBankConfig);
+}
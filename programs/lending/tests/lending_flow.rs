@@ -0,0 +1,424 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use lending::decimal::Decimal;
+use lending::errors::ErrorCode;
+use lending::math_utils::calc_virtual_deposit_shares;
+use lending::oracle_utils::get_cached_bank_price;
+use lending_client::{
+    accrued_bank, find_bank_address, find_treasury_address, get_associated_token_address,
+    position_borrowed_amount, position_deposited_amount,
+};
+use solana_sdk::signature::Signer;
+
+const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
+#[tokio::test]
+async fn init_bank_sets_config_and_indices() {
+    let mut test_context = TestContext::new().await;
+    let sol_mint = test_context.sol_mint;
+
+    let bank = test_context.bank(&sol_mint).await;
+    assert_eq!(bank.authority, test_context.admin.pubkey());
+    assert_eq!(bank.mint, sol_mint);
+    assert_eq!(bank.mint_decimals, SOL_DECIMALS);
+    assert_eq!(bank.oracle_config, oracle_config(SOL_FEED_ID));
+    assert_eq!(bank.max_ltv, sol_bank_config().max_ltv);
+    assert_eq!(bank.supply_index, Decimal::ONE);
+    assert_eq!(bank.borrow_index, Decimal::ONE);
+}
+
+#[tokio::test]
+async fn deposit_borrow_repay_withdraw_with_interest() {
+    let mut test_context = TestContext::new().await;
+    let (sol_mint, usdc_mint) = (test_context.sol_mint, test_context.usdc_mint);
+    let sol_bank = find_bank_address(&sol_mint).0;
+
+    let lender = test_context.create_user(0, 10_000 * USDC).await;
+    let borrower = test_context.create_user(10 * SOL, 0).await;
+
+    let deposit = lending_client::deposit(
+        lender.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        10_000 * USDC,
+        &[],
+    );
+    test_context.process(&[deposit], &[&lender]).await.unwrap();
+    let deposit = lending_client::deposit(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        10 * SOL,
+        &[],
+    );
+    test_context
+        .process(&[deposit], &[&borrower])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context
+            .token_balance(&borrower.pubkey(), &sol_mint)
+            .await,
+        0
+    );
+    let sol_bank_account = test_context.bank(&sol_mint).await;
+    assert_eq!(
+        sol_bank_account.total_deposited_amount,
+        10 * SOL + calc_virtual_deposit_shares(SOL_DECIMALS).unwrap()
+    );
+    assert_eq!(
+        test_context.receipt_supply(&sol_mint).await,
+        sol_bank_account.total_deposited_shares
+    );
+
+    //10 SOL * $150 * 0.75 = $1125 可借额度
+    let borrow = lending_client::borrow(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        Decimal::from_u64(1_000),
+        &[sol_bank],
+    );
+    test_context.process(&[borrow], &[&borrower]).await.unwrap();
+    assert_eq!(
+        test_context
+            .token_balance(&borrower.pubkey(), &usdc_mint)
+            .await,
+        1_000 * USDC
+    );
+    let user = test_context.user(&borrower.pubkey()).await;
+    assert_eq!(user.positions.len(), 2);
+    //健康因子 = 1500 * 0.8 / 1000
+    assert_eq!(user.health_factor, Decimal::from_bps(12_000));
+
+    test_context.warp_seconds(SECONDS_PER_YEAR).await;
+    let now = test_context.unix_timestamp().await;
+    let usdc_bank = accrued_bank(&test_context.bank(&usdc_mint).await, now).unwrap();
+    let user = test_context.user(&borrower.pubkey()).await;
+    let debt = position_borrowed_amount(
+        &usdc_bank,
+        user.find_position(&find_bank_address(&usdc_mint).0)
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(debt > 1_000 * USDC);
+    assert!(usdc_bank.supply_index > Decimal::ONE);
+    assert!(usdc_bank.accumulated_protocol_fees > 0);
+
+    test_context.set_token_balance(&borrower.pubkey(), &usdc_mint, debt);
+    let repay = lending_client::repay(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        debt,
+        &[],
+    );
+    test_context.process(&[repay], &[&borrower]).await.unwrap();
+    let user = test_context.user(&borrower.pubkey()).await;
+    assert_eq!(user.positions.len(), 1);
+    assert_eq!(user.health_factor, Decimal::MAX);
+    assert_eq!(test_context.bank(&usdc_mint).await.total_borrowed_shares, 0);
+
+    let withdraw = lending_client::withdraw(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        10 * SOL,
+        &[],
+    );
+    test_context
+        .process(&[withdraw], &[&borrower])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context
+            .token_balance(&borrower.pubkey(), &sol_mint)
+            .await,
+        10 * SOL
+    );
+    assert!(test_context
+        .user(&borrower.pubkey())
+        .await
+        .positions
+        .is_empty());
+
+    //出借人取回本金和利息
+    let usdc_bank = test_context.bank(&usdc_mint).await;
+    let user = test_context.user(&lender.pubkey()).await;
+    let deposited = position_deposited_amount(&usdc_bank, &user.positions[0]).unwrap();
+    assert!(deposited > 10_000 * USDC);
+    let withdraw = lending_client::withdraw(
+        lender.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        deposited,
+        &[],
+    );
+    test_context.process(&[withdraw], &[&lender]).await.unwrap();
+    assert_eq!(
+        test_context
+            .token_balance(&lender.pubkey(), &usdc_mint)
+            .await,
+        deposited
+    );
+}
+
+#[tokio::test]
+async fn health_factor_tracks_deposit_and_repay() {
+    let mut test_context = TestContext::new().await;
+    let (sol_mint, usdc_mint) = (test_context.sol_mint, test_context.usdc_mint);
+    let (sol_bank, usdc_bank) = (
+        find_bank_address(&sol_mint).0,
+        find_bank_address(&usdc_mint).0,
+    );
+
+    let lender = test_context.create_user(0, 10_000 * USDC).await;
+    let borrower = test_context.create_user(15 * SOL, 0).await;
+    assert_eq!(
+        test_context.user(&borrower.pubkey()).await.health_factor,
+        Decimal::MAX
+    );
+
+    let deposit = lending_client::deposit(
+        lender.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        10_000 * USDC,
+        &[],
+    );
+    test_context.process(&[deposit], &[&lender]).await.unwrap();
+    let deposit = lending_client::deposit(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        10 * SOL,
+        &[],
+    );
+    test_context
+        .process(&[deposit], &[&borrower])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context.user(&borrower.pubkey()).await.health_factor,
+        Decimal::MAX
+    );
+
+    let borrow = lending_client::borrow(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        Decimal::from_u64(1_000),
+        &[sol_bank],
+    );
+    test_context.process(&[borrow], &[&borrower]).await.unwrap();
+
+    //健康因子 = 15 SOL * $150 * 0.8 / 1000
+    let deposit = lending_client::deposit(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        5 * SOL,
+        &[usdc_bank],
+    );
+    test_context
+        .process(&[deposit], &[&borrower])
+        .await
+        .unwrap();
+    assert_eq!(
+        test_context.user(&borrower.pubkey()).await.health_factor,
+        Decimal::from_bps(18_000)
+    );
+
+    //部分还款后 = 1800 / 500
+    let repay = lending_client::repay(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        500 * USDC,
+        &[sol_bank],
+    );
+    test_context.process(&[repay], &[&borrower]).await.unwrap();
+    assert_eq!(
+        test_context.user(&borrower.pubkey()).await.health_factor,
+        Decimal::from_bps(36_000)
+    );
+}
+
+#[tokio::test]
+async fn donation_cannot_steal_from_next_depositor() {
+    let mut test_context = TestContext::new().await;
+    let usdc_mint = test_context.usdc_mint;
+    let attacker_balance = 1_010_000 * USDC;
+    let attacker = test_context.create_user(0, attacker_balance).await;
+    let victim = test_context.create_user(0, 1_000 * USDC).await;
+    let attacker_token_account =
+        get_associated_token_address(&attacker.pubkey(), &usdc_mint, &spl_token::ID);
+
+    //攻击者存入 1 个最小单位, 再直接向 treasury 捐赠 1000000 USDC
+    let deposit = lending_client::deposit(
+        attacker.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        1,
+        &[],
+    );
+    let donate = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &attacker_token_account,
+        &find_treasury_address(&usdc_mint).0,
+        &attacker.pubkey(),
+        &[],
+        1_000_000 * USDC,
+    )
+    .unwrap();
+    test_context
+        .process(&[deposit, donate], &[&attacker])
+        .await
+        .unwrap();
+
+    //借出全部流动性, 用闪电贷手续费抬高 supply_index
+    let flash_borrow = lending_client::flash_borrow(
+        attacker.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        attacker_token_account,
+        1_000_000 * USDC + 1,
+    );
+    let flash_repay = lending_client::flash_repay(
+        attacker.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        attacker_token_account,
+    );
+    test_context
+        .process(&[flash_borrow, flash_repay], &[&attacker])
+        .await
+        .unwrap();
+
+    let deposit = lending_client::deposit(
+        victim.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        1_000 * USDC,
+        &[],
+    );
+    test_context.process(&[deposit], &[&victim]).await.unwrap();
+
+    let bank = test_context.bank(&usdc_mint).await;
+    let victim_user = test_context.user(&victim.pubkey()).await;
+    let attacker_user = test_context.user(&attacker.pubkey()).await;
+    let victim_amount = position_deposited_amount(&bank, &victim_user.positions[0]).unwrap();
+    let attacker_amount = position_deposited_amount(&bank, &attacker_user.positions[0]).unwrap();
+    //份额取整损失不超过 1 USDC
+    let victim_loss = 1_000 * USDC - victim_amount;
+    assert!(victim_loss <= USDC);
+
+    for (owner, amount) in [(&victim, victim_amount), (&attacker, attacker_amount)] {
+        let withdraw = lending_client::withdraw(
+            owner.pubkey(),
+            usdc_mint,
+            spl_token::ID,
+            test_context.usdc_price_update,
+            amount,
+            &[],
+        );
+        test_context.process(&[withdraw], &[owner]).await.unwrap();
+    }
+    assert_eq!(
+        test_context
+            .token_balance(&victim.pubkey(), &usdc_mint)
+            .await,
+        victim_amount
+    );
+    //捐赠和手续费绝大部分归虚拟份额, 攻击者的损失远大于受害者
+    let attacker_loss = attacker_balance
+        - test_context
+            .token_balance(&attacker.pubkey(), &usdc_mint)
+            .await;
+    assert!(attacker_loss > 1_000 * victim_loss);
+}
+
+#[tokio::test]
+async fn refresh_bank_accrues_interest_and_caches_price() {
+    let mut test_context = TestContext::new().await;
+    let (sol_mint, usdc_mint) = (test_context.sol_mint, test_context.usdc_mint);
+    let lender = test_context.create_user(0, 10_000 * USDC).await;
+    let borrower = test_context.create_user(10 * SOL, 0).await;
+    let deposit = lending_client::deposit(
+        lender.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        10_000 * USDC,
+        &[],
+    );
+    test_context.process(&[deposit], &[&lender]).await.unwrap();
+    let deposit = lending_client::deposit(
+        borrower.pubkey(),
+        sol_mint,
+        spl_token::ID,
+        test_context.sol_price_update,
+        10 * SOL,
+        &[],
+    );
+    test_context
+        .process(&[deposit], &[&borrower])
+        .await
+        .unwrap();
+    let borrow = lending_client::borrow(
+        borrower.pubkey(),
+        usdc_mint,
+        spl_token::ID,
+        test_context.usdc_price_update,
+        Decimal::from_u64(1_000),
+        &[find_bank_address(&sol_mint).0],
+    );
+    test_context.process(&[borrow], &[&borrower]).await.unwrap();
+    let published_at = test_context
+        .bank(&usdc_mint)
+        .await
+        .cached_price
+        .publish_time;
+
+    test_context.warp_seconds(SECONDS_PER_YEAR / 12).await;
+    let refresh = lending_client::refresh_bank(usdc_mint, test_context.sol_price_update);
+    let result = test_context.process(&[refresh], &[]).await;
+    assert_error(result, ErrorCode::InvalidOracleAccount);
+
+    //任何人都可以调用, 只需支付交易费
+    let refresh = lending_client::refresh_bank(usdc_mint, test_context.usdc_price_update);
+    test_context.process(&[refresh], &[]).await.unwrap();
+    let now = test_context.unix_timestamp().await;
+    let bank = test_context.bank(&usdc_mint).await;
+    assert_eq!(bank.last_updated, now);
+    assert!(bank.borrow_index > Decimal::ONE);
+    assert!(bank.supply_index > Decimal::ONE);
+    assert!(bank.total_borrowed_amount > 1_000 * USDC);
+    assert_eq!(bank.cached_price.price, USDC_PRICE);
+    assert_eq!(bank.cached_price.exponent, PRICE_EXPONENT);
+    assert_eq!(bank.cached_price.publish_time, now);
+    assert!(bank.cached_price.publish_time > published_at);
+
+    let mut clock = test_context.clock().await;
+    assert_eq!(
+        get_cached_bank_price(&bank, &clock).unwrap(),
+        bank.cached_price
+    );
+    clock.unix_timestamp = now + MAX_AGE as i64 + 1;
+    assert!(get_cached_bank_price(&bank, &clock).is_err());
+}
@@ -0,0 +1,256 @@
+use anchor_lang::Result;
+use lending::constants::SECONDS_PER_YEAR;
+use lending::decimal::{mul_div, Decimal};
+use lending::enums::Rounding;
+use lending::errors::ErrorCode;
+use lending::math_utils::{calc_change_shares, calc_shares_value, calc_virtual_deposit_shares};
+use lending::states::Bank;
+use proptest::prelude::*;
+
+const USERS: usize = 4;
+const DECIMALS: u8 = 6;
+const MAX_AMOUNT: u64 = 1_000_000_000_000;
+const MAX_RATE_BPS: u64 = 10_000;
+///借款份额远大于常规存款, 利用率接近 100%, 存款利率接近 base_rate
+const BORROWED_SHARES: u64 = 1 << 50;
+///份额记账允许返回的错误, 其他错误或 panic 均视为失败
+const EXPECTED_ERRORS: [ErrorCode; 3] = [
+    ErrorCode::MathOverflow,
+    ErrorCode::DepositTooSmall,
+    ErrorCode::NotEnoughBalance,
+];
+
+#[derive(Clone, Debug)]
+enum Op {
+    Deposit { user: usize, amount: u64 },
+    Withdraw { user: usize, amount: u64 },
+    WithdrawAll { user: usize },
+    Accrue { rate_bps: u64, elapsed: i64 },
+}
+
+///大部分金额在常规范围内, 其余覆盖整个 u64 以触发溢出
+fn amount_strategy() -> impl Strategy<Value = u64> {
+    prop_oneof![3 => 1..=MAX_AMOUNT, 1 => any::<u64>()]
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..USERS, amount_strategy()).prop_map(|(user, amount)| Op::Deposit { user, amount }),
+        (0..USERS, amount_strategy()).prop_map(|(user, amount)| Op::Withdraw { user, amount }),
+        (0..USERS).prop_map(|user| Op::WithdrawAll { user }),
+        (0..=MAX_RATE_BPS, 0..=SECONDS_PER_YEAR as i64)
+            .prop_map(|(rate_bps, elapsed)| Op::Accrue { rate_bps, elapsed }),
+    ]
+}
+
+///与 init_bank 相同的初始状态
+fn new_bank() -> Bank {
+    let virtual_deposit_shares = calc_virtual_deposit_shares(DECIMALS).unwrap();
+    Bank {
+        mint_decimals: DECIMALS,
+        supply_index: Decimal::ONE,
+        borrow_index: Decimal::ONE,
+        total_deposited_amount: virtual_deposit_shares,
+        total_deposited_shares: virtual_deposit_shares,
+        total_borrowed_amount: BORROWED_SHARES,
+        total_borrowed_shares: BORROWED_SHARES,
+        ..Default::default()
+    }
+}
+
+///在 bank 的副本上执行, 出错时与交易回滚一样丢弃修改
+fn try_apply<T>(bank: &mut Bank, op: impl FnOnce(&mut Bank) -> Result<T>) -> Option<T> {
+    let mut next = bank.clone();
+    match op(&mut next) {
+        Ok(value) => {
+            *bank = next;
+            Some(value)
+        }
+        Err(error) => {
+            assert!(
+                EXPECTED_ERRORS.iter().any(|&code| error == code.into()),
+                "unexpected error: {error:?}"
+            );
+            None
+        }
+    }
+}
+
+///通过 Bank 的存取款记账驱动多个用户
+struct Pool {
+    bank: Bank,
+    shares: [u64; USERS],
+    ///用户最多可取回的金额: 存入本金按指数增长, 向上取整
+    claims: [Decimal; USERS],
+}
+
+impl Pool {
+    fn new() -> Self {
+        Pool {
+            bank: new_bank(),
+            shares: [0; USERS],
+            claims: [Decimal::ZERO; USERS],
+        }
+    }
+
+    fn user_shares(&self) -> u128 {
+        self.shares.iter().map(|&shares| shares as u128).sum()
+    }
+
+    fn deposit(&mut self, user: usize, amount: u64) -> bool {
+        let Some(shares) = try_apply(&mut self.bank, |bank| bank.add_deposit(amount)) else {
+            return false;
+        };
+        self.shares[user] += shares;
+        self.claims[user] = self.claims[user]
+            .try_add(Decimal::from_u64(amount))
+            .unwrap();
+
+        true
+    }
+
+    fn withdraw(&mut self, user: usize, amount: u64) -> u64 {
+        let user_shares = self.shares[user];
+        let Some(shares) = try_apply(&mut self.bank, |bank| {
+            bank.remove_deposit(amount, user_shares)
+        }) else {
+            return 0;
+        };
+        self.shares[user] -= shares;
+        self.claims[user] = self.claims[user]
+            .try_sub(Decimal::from_u64(amount))
+            .expect("withdrew more than deposited plus interest");
+
+        amount
+    }
+
+    fn withdraw_all(&mut self, user: usize) -> u64 {
+        let value =
+            calc_shares_value(self.shares[user], self.bank.supply_index, Rounding::Down).unwrap();
+        self.withdraw(user, value)
+    }
+
+    fn accrue(&mut self, rate_bps: u64, elapsed: i64) {
+        let index = self.bank.supply_index;
+        let accrued = try_apply(&mut self.bank, |bank| {
+            bank.interest_rate_model.base_rate = Decimal::from_bps(rate_bps);
+            bank.update_interest(bank.last_updated + elapsed)
+        });
+        if accrued.is_none() {
+            return;
+        }
+        let new_index = self.bank.supply_index;
+        for claim in self.claims.iter_mut() {
+            *claim = Decimal(mul_div(claim.0, new_index.0, index.0, Rounding::Up).unwrap());
+        }
+    }
+
+    fn apply(&mut self, op: Op) {
+        match op {
+            Op::Deposit { user, amount } => {
+                self.deposit(user, amount);
+            }
+            Op::Withdraw { user, amount } => {
+                self.withdraw(user, amount);
+            }
+            Op::WithdrawAll { user } => {
+                self.withdraw_all(user);
+            }
+            Op::Accrue { rate_bps, elapsed } => self.accrue(rate_bps, elapsed),
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn user_shares_sum_to_total_shares(ops in prop::collection::vec(op_strategy(), 1..64)) {
+        let mut pool = Pool::new();
+        for op in ops {
+            pool.apply(op);
+            prop_assert_eq!(
+                pool.user_shares() + pool.bank.virtual_deposit_shares().unwrap() as u128,
+                pool.bank.total_deposited_shares as u128
+            );
+        }
+    }
+
+    #[test]
+    fn withdraw_all_never_exceeds_deposits_plus_interest(
+        ops in prop::collection::vec(op_strategy(), 1..64),
+    ) {
+        let mut pool = Pool::new();
+        for op in ops {
+            pool.apply(op);
+        }
+        for user in 0..USERS {
+            let claim = pool.claims[user];
+            let withdrawn = pool.withdraw_all(user);
+            prop_assert!(Decimal::from_u64(withdrawn) <= claim);
+            prop_assert_eq!(pool.shares[user], 0);
+        }
+        prop_assert_eq!(
+            pool.bank.total_deposited_shares,
+            pool.bank.virtual_deposit_shares().unwrap()
+        );
+    }
+
+    #[test]
+    fn round_trips_never_create_value(
+        rate_bps in 0..=MAX_RATE_BPS,
+        elapsed in 0..=SECONDS_PER_YEAR as i64,
+        balance in amount_strategy(),
+        partial in prop::collection::vec(amount_strategy(), 1..16),
+    ) {
+        let mut pool = Pool::new();
+        pool.accrue(rate_bps, elapsed);
+
+        let mut current = balance;
+        for amount in partial {
+            if !pool.deposit(0, current) {
+                break;
+            }
+            let mut withdrawn = pool.withdraw(0, amount);
+            withdrawn += pool.withdraw_all(0);
+            prop_assert!(withdrawn <= current);
+            prop_assert_eq!(
+                pool.bank.total_deposited_shares,
+                pool.bank.virtual_deposit_shares().unwrap()
+            );
+            current = withdrawn;
+        }
+    }
+
+    #[test]
+    fn change_shares_round_in_protocol_favor(
+        amount in 0..=MAX_AMOUNT,
+        rate_bps in 0..=MAX_RATE_BPS,
+        elapsed in 0..=SECONDS_PER_YEAR as i64,
+    ) {
+        let mut pool = Pool::new();
+        pool.accrue(rate_bps, elapsed);
+        let index = pool.bank.supply_index;
+
+        let shares_down = calc_change_shares(amount, index, Rounding::Down).unwrap();
+        let shares_up = calc_change_shares(amount, index, Rounding::Up).unwrap();
+        prop_assert!(shares_up - shares_down <= 1);
+        prop_assert!(calc_shares_value(shares_down, index, Rounding::Down).unwrap() <= amount);
+        prop_assert!(calc_shares_value(shares_up, index, Rounding::Up).unwrap() >= amount);
+    }
+}
+
+#[test]
+fn deposit_overflow_is_rejected() {
+    let mut bank = new_bank();
+    bank.total_deposited_amount = u64::MAX;
+    let result = bank.add_deposit(1);
+    assert_eq!(result.unwrap_err(), ErrorCode::MathOverflow.into());
+    assert_eq!(
+        bank.total_deposited_shares,
+        bank.virtual_deposit_shares().unwrap()
+    );
+}
+
+#[test]
+fn zero_index_is_rejected() {
+    assert!(calc_change_shares(1, Decimal::ZERO, Rounding::Down).is_err());
+}